@@ -33,12 +33,31 @@ pub enum Commands {
         /// Use a custom string instead of generating a random one
         #[arg(short, long)]
         custom: Option<String>,
+
+        /// Deterministic campaign seed (lagged Fibonacci generator). Same
+        /// seed always reproduces the same string, so it can be regenerated
+        /// offline for attribution without storing the plaintext mapping.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Hex-encoded master key for HKDF-derived, cryptographically
+        /// attributable strings. Requires `--label`.
+        #[arg(long, requires = "label")]
+        master_key: Option<String>,
+
+        /// Recipient label the string is derived for (the HKDF `info`
+        /// parameter). Requires `--master-key`.
+        #[arg(long, requires = "master_key")]
+        label: Option<String>,
     },
 
     /// Generate a YARA rule for a tracking string
     Yara {
-        /// The tracking string to create a rule for
-        string: String,
+        /// The tracking string to create a rule for. Omit with `--from-db`,
+        /// which sources the patterns from the tracked-string database
+        /// instead.
+        #[arg(required_unless_present = "from_db")]
+        string: Option<String>,
 
         /// Include ASCII string matching
         #[arg(long, default_value = "true")]
@@ -48,10 +67,40 @@ pub enum Commands {
         #[arg(long)]
         wide: bool,
 
+        /// Case-insensitive string matching
+        #[arg(long)]
+        nocase: bool,
+
+        /// Match the string only as a whole word, not as a substring
+        #[arg(long)]
+        fullword: bool,
+
         /// Rule name (defaults to auto-generated)
         #[arg(short, long)]
         name: Option<String>,
 
+        /// Pull every tracked string from the database instead of a single
+        /// `string` argument, producing one consolidated rule with every
+        /// matching value as its own string identifier
+        #[arg(long, conflicts_with = "string")]
+        from_db: bool,
+
+        /// Restrict `--from-db` to strings whose tags match this filter
+        #[arg(long, requires = "from_db")]
+        tag: Option<String>,
+
+        /// Require every string to match (`all of them`) instead of any one
+        /// of them (`any of them`); only meaningful with `--from-db`
+        #[arg(long, requires = "from_db")]
+        all: bool,
+
+        /// Emit one rule per tag instead of a single consolidated rule.
+        /// Strings with more than one tag appear in more than one rule;
+        /// untagged strings are skipped. Conflicts with `--tag`, which
+        /// already narrows `--from-db` to a single tag.
+        #[arg(long, requires = "from_db", conflicts_with = "tag")]
+        per_tag: bool,
+
         /// Output file path
         #[arg(short, long)]
         output: Option<PathBuf>,
@@ -66,6 +115,34 @@ pub enum Commands {
         #[arg(short, long, value_enum, default_value = "c")]
         language: Language,
 
+        /// Yaz0-compress the payload and emit a decoder, instead of a
+        /// plaintext literal, to defeat naive `strings` scans
+        #[arg(long)]
+        encoded: bool,
+
+        /// Reconstruct the string at runtime instead of embedding it as a
+        /// literal, so it doesn't sit whole in the binary's data section.
+        /// Only applies to the built-in per-language generators.
+        #[arg(long, value_enum, default_value = "none", conflicts_with_all = ["template", "generators"])]
+        encode: Encoding,
+
+        /// Render a user-supplied template instead of a built-in generator,
+        /// for languages/formats this crate has no generator for. `language`
+        /// still selects the `{{escaped}}` escaping rules.
+        #[arg(long, conflicts_with = "generators")]
+        template: Option<PathBuf>,
+
+        /// Path to a JSON config of named custom generators (a team's
+        /// in-house library of `--template`-style snippets), to pick one
+        /// from with `--generator` instead of pasting a one-off file path
+        #[arg(long)]
+        generators: Option<PathBuf>,
+
+        /// Which entry in `--generators` to render; defaults to the
+        /// config's only entry if it has just one
+        #[arg(long, requires = "generators")]
+        generator: Option<String>,
+
         /// Output file path
         #[arg(short, long)]
         output: Option<PathBuf>,
@@ -90,6 +167,29 @@ pub enum Commands {
         /// Force patching even if it may break the binary
         #[arg(long)]
         force: bool,
+
+        /// Wrap the string in a Reed-Solomon erasure code before embedding
+        /// it, so `recover` can reconstruct it even if the patch is
+        /// partially overwritten or stripped
+        #[arg(long)]
+        resilient: bool,
+
+        /// Total Reed-Solomon codeword symbols (n). Only used with
+        /// `--resilient`.
+        #[arg(long, default_value = "20")]
+        rs_n: u8,
+
+        /// Reed-Solomon data symbols (k) the string is padded into; the
+        /// remaining `n - k` are parity. Only used with `--resilient`.
+        #[arg(long, default_value = "16")]
+        rs_k: u8,
+    },
+
+    /// Reconstruct a tracking string from a Reed-Solomon-resilient patch,
+    /// even if it was partially overwritten or stripped
+    Recover {
+        /// Path to the (possibly damaged) binary to recover a string from
+        binary: PathBuf,
     },
 
     /// List all tracked strings
@@ -108,16 +208,106 @@ pub enum Commands {
         /// The tracking string or UUID to show
         identifier: String,
     },
+
+    /// Export tracked strings as a compact, line-oriented raw-hex format,
+    /// for sharing a breadcrumb corpus with other analysts or diffing two
+    /// databases
+    Export {
+        /// Write the exported vectors here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only export strings carrying this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+
+    /// Import tracked strings from the raw-hex vector format `export`
+    /// produces, skipping any value already present in the database
+    Import {
+        /// Path to the exported vector file to import
+        input: PathBuf,
+    },
+
+    /// Recompute an HKDF-derived tracking string for every candidate label
+    /// and report which one produced a leaked fragment
+    Attribute {
+        /// The leaked string fragment to attribute
+        fragment: String,
+
+        /// Prefix the derived strings were generated with
+        #[arg(short, long, default_value = "RT")]
+        prefix: String,
+
+        /// Hex-encoded master key the strings were derived from
+        #[arg(long)]
+        master_key: String,
+
+        /// Candidate recipient labels to check, in order
+        #[arg(short, long = "label", required = true)]
+        labels: Vec<String>,
+    },
+
+    /// Scan a file or directory for embedded tracking strings from the
+    /// database (the reverse of `patch`)
+    Scan {
+        /// File or directory to scan
+        path: PathBuf,
+
+        /// Only scan for tracked strings carrying this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Also match the UTF-16LE encoding of each tracked string
+        #[arg(long)]
+        wide: bool,
+    },
+
+    /// Run many generate/code/yara/patch jobs from a single JSON manifest in
+    /// one pass, so a whole campaign of breadcrumbs can be seeded
+    /// reproducibly instead of scripting the CLI call-by-call
+    Batch {
+        /// Path to the JSON workload manifest (an array of job objects)
+        manifest: PathBuf,
+
+        /// Write the machine-readable summary report here instead of stdout
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+
+    /// Generate a shell completion script or a man page, derived straight
+    /// from this `Cli` definition so it can never drift out of sync with
+    /// the actual flags
+    Completions {
+        /// Shell to generate a completion script for
+        #[arg(value_enum, required_unless_present = "man")]
+        shell: Option<clap_complete::Shell>,
+
+        /// Render a roff man page instead of a shell completion script
+        #[arg(long, conflicts_with = "shell")]
+        man: bool,
+    },
 }
 
 #[derive(Clone, Copy, ValueEnum)]
 pub enum Language {
     C,
     Cpp,
+    Python,
     Go,
     Rust,
     Csharp,
+    Javascript,
+    Powershell,
     Java,
+    /// Linkable ELF ET_REL object (`tracking.o`), no compiler required
+    ElfObject,
+    /// Linkable COFF object (`tracking.obj`), no compiler required
+    CoffObject,
 }
 
 impl std::fmt::Display for Language {
@@ -125,10 +315,38 @@ impl std::fmt::Display for Language {
         match self {
             Language::C => write!(f, "c"),
             Language::Cpp => write!(f, "cpp"),
+            Language::Python => write!(f, "python"),
             Language::Go => write!(f, "go"),
             Language::Rust => write!(f, "rust"),
             Language::Csharp => write!(f, "csharp"),
+            Language::Javascript => write!(f, "javascript"),
+            Language::Powershell => write!(f, "powershell"),
             Language::Java => write!(f, "java"),
+            Language::ElfObject => write!(f, "elf-object"),
+            Language::CoffObject => write!(f, "coff-object"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Encoding {
+    /// Emit the tracking string as a plain literal (or, with `--encoded`,
+    /// the Yaz0-compressed byte array) — current behavior
+    None,
+    /// XOR every byte with a single random key byte, stored as a byte array
+    /// alongside the key and decoded by a loop at startup
+    Xor,
+    /// Assign each character into successive elements of a local buffer one
+    /// at a time, so no constant string literal exists in the binary at all
+    Stack,
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encoding::None => write!(f, "none"),
+            Encoding::Xor => write!(f, "xor"),
+            Encoding::Stack => write!(f, "stack"),
         }
     }
 }
@@ -143,6 +361,9 @@ pub enum PatchStrategy {
     Extend,
     /// Append data as overlay (past file end)
     Overlay,
+    /// Embed the string in a well-formed ELF note (ELF only), surviving
+    /// `strip`/objcopy far more reliably than a raw cave or segment write
+    Note,
 }
 
 impl std::fmt::Display for PatchStrategy {
@@ -152,6 +373,7 @@ impl std::fmt::Display for PatchStrategy {
             PatchStrategy::Section => write!(f, "section"),
             PatchStrategy::Extend => write!(f, "extend"),
             PatchStrategy::Overlay => write!(f, "overlay"),
+            PatchStrategy::Note => write!(f, "note"),
         }
     }
 }
@@ -189,6 +411,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_with_seed() {
+        let cli = Cli::try_parse_from(["bredcrumb", "generate", "--seed", "1234"]).unwrap();
+        match cli.command {
+            Commands::Generate { seed, .. } => {
+                assert_eq!(seed, Some(1234));
+            }
+            _ => panic!("Expected Generate command"),
+        }
+    }
+
+    #[test]
+    fn test_generate_with_master_key_and_label() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "generate",
+            "--master-key",
+            "deadbeef",
+            "--label",
+            "alice@example.com",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Generate {
+                master_key, label, ..
+            } => {
+                assert_eq!(master_key, Some("deadbeef".to_string()));
+                assert_eq!(label, Some("alice@example.com".to_string()));
+            }
+            _ => panic!("Expected Generate command"),
+        }
+    }
+
+    #[test]
+    fn test_generate_master_key_requires_label() {
+        let result = Cli::try_parse_from(["bredcrumb", "generate", "--master-key", "deadbeef"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_attribute_command() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "attribute",
+            "RTFRAGMENT",
+            "--master-key",
+            "deadbeef",
+            "-l",
+            "alice@example.com",
+            "-l",
+            "bob@example.com",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Attribute {
+                fragment,
+                master_key,
+                labels,
+                ..
+            } => {
+                assert_eq!(fragment, "RTFRAGMENT");
+                assert_eq!(master_key, "deadbeef");
+                assert_eq!(labels, vec!["alice@example.com", "bob@example.com"]);
+            }
+            _ => panic!("Expected Attribute command"),
+        }
+    }
+
     #[test]
     fn test_yara_command() {
         let cli = Cli::try_parse_from(["bredcrumb", "yara", "TEST123"]).unwrap();
@@ -199,7 +489,7 @@ mod tests {
                 wide,
                 ..
             } => {
-                assert_eq!(string, "TEST123");
+                assert_eq!(string.as_deref(), Some("TEST123"));
                 assert!(ascii);
                 assert!(!wide);
             }
@@ -218,6 +508,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_yara_requires_string_or_from_db() {
+        let result = Cli::try_parse_from(["bredcrumb", "yara"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_yara_from_db() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "yara",
+            "--from-db",
+            "--tag",
+            "campaign-1",
+            "--all",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Yara {
+                string,
+                from_db,
+                tag,
+                all,
+                ..
+            } => {
+                assert_eq!(string, None);
+                assert!(from_db);
+                assert_eq!(tag.as_deref(), Some("campaign-1"));
+                assert!(all);
+            }
+            _ => panic!("Expected Yara command"),
+        }
+    }
+
+    #[test]
+    fn test_yara_from_db_conflicts_with_string() {
+        let result = Cli::try_parse_from(["bredcrumb", "yara", "TEST123", "--from-db"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_yara_per_tag() {
+        let cli = Cli::try_parse_from(["bredcrumb", "yara", "--from-db", "--per-tag"]).unwrap();
+        match cli.command {
+            Commands::Yara {
+                from_db, per_tag, ..
+            } => {
+                assert!(from_db);
+                assert!(per_tag);
+            }
+            _ => panic!("Expected Yara command"),
+        }
+    }
+
+    #[test]
+    fn test_yara_per_tag_conflicts_with_tag() {
+        let result = Cli::try_parse_from([
+            "bredcrumb",
+            "yara",
+            "--from-db",
+            "--tag",
+            "campaign-1",
+            "--per-tag",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_yara_nocase_fullword() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "yara",
+            "TEST123",
+            "--nocase",
+            "--fullword",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Yara {
+                nocase, fullword, ..
+            } => {
+                assert!(nocase);
+                assert!(fullword);
+            }
+            _ => panic!("Expected Yara command"),
+        }
+    }
+
     #[test]
     fn test_code_command() {
         let cli = Cli::try_parse_from(["bredcrumb", "code", "TEST123"]).unwrap();
@@ -232,6 +610,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_code_with_encoded() {
+        let cli = Cli::try_parse_from(["bredcrumb", "code", "TEST123", "--encoded"]).unwrap();
+        match cli.command {
+            Commands::Code { encoded, .. } => {
+                assert!(encoded);
+            }
+            _ => panic!("Expected Code command"),
+        }
+    }
+
     #[test]
     fn test_code_with_language() {
         let cli = Cli::try_parse_from(["bredcrumb", "code", "TEST123", "-l", "rust"]).unwrap();
@@ -243,6 +632,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_code_with_template() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "code",
+            "TEST123",
+            "--template",
+            "custom.tmpl",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Code { template, .. } => {
+                assert_eq!(template, Some(PathBuf::from("custom.tmpl")));
+            }
+            _ => panic!("Expected Code command"),
+        }
+    }
+
+    #[test]
+    fn test_code_with_generators() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "code",
+            "TEST123",
+            "--generators",
+            "generators.json",
+            "--generator",
+            "nim",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Code { generators, generator, .. } => {
+                assert_eq!(generators, Some(PathBuf::from("generators.json")));
+                assert_eq!(generator, Some("nim".to_string()));
+            }
+            _ => panic!("Expected Code command"),
+        }
+    }
+
+    #[test]
+    fn test_code_generator_requires_generators() {
+        let result = Cli::try_parse_from([
+            "bredcrumb",
+            "code",
+            "TEST123",
+            "--generator",
+            "nim",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_code_defaults_to_no_encoding() {
+        let cli = Cli::try_parse_from(["bredcrumb", "code", "TEST123"]).unwrap();
+        match cli.command {
+            Commands::Code { encode, .. } => {
+                assert!(matches!(encode, Encoding::None));
+            }
+            _ => panic!("Expected Code command"),
+        }
+    }
+
+    #[test]
+    fn test_code_with_encode_xor() {
+        let cli =
+            Cli::try_parse_from(["bredcrumb", "code", "TEST123", "--encode", "xor"]).unwrap();
+        match cli.command {
+            Commands::Code { encode, .. } => {
+                assert!(matches!(encode, Encoding::Xor));
+            }
+            _ => panic!("Expected Code command"),
+        }
+    }
+
+    #[test]
+    fn test_code_encode_conflicts_with_template() {
+        let result = Cli::try_parse_from([
+            "bredcrumb",
+            "code",
+            "TEST123",
+            "--encode",
+            "stack",
+            "--template",
+            "custom.tmpl",
+        ]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_patch_command() {
         let cli = Cli::try_parse_from(["bredcrumb", "patch", "/tmp/test.exe", "TRACKER"]).unwrap();
@@ -280,6 +757,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_patch_with_resilient() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "patch",
+            "/tmp/test.exe",
+            "TRACKER",
+            "--resilient",
+            "--rs-n",
+            "24",
+            "--rs-k",
+            "12",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Patch {
+                resilient,
+                rs_n,
+                rs_k,
+                ..
+            } => {
+                assert!(resilient);
+                assert_eq!(rs_n, 24);
+                assert_eq!(rs_k, 12);
+            }
+            _ => panic!("Expected Patch command"),
+        }
+    }
+
+    #[test]
+    fn test_recover_command() {
+        let cli = Cli::try_parse_from(["bredcrumb", "recover", "/tmp/damaged.exe"]).unwrap();
+        match cli.command {
+            Commands::Recover { binary } => {
+                assert_eq!(binary.to_str().unwrap(), "/tmp/damaged.exe");
+            }
+            _ => panic!("Expected Recover command"),
+        }
+    }
+
     #[test]
     fn test_list_command() {
         let cli = Cli::try_parse_from(["bredcrumb", "list"]).unwrap();
@@ -314,6 +831,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_export_command() {
+        let cli = Cli::try_parse_from(["bredcrumb", "export", "--tag", "campaign-1"]).unwrap();
+        match cli.command {
+            Commands::Export { output, tag } => {
+                assert!(output.is_none());
+                assert_eq!(tag.as_deref(), Some("campaign-1"));
+            }
+            _ => panic!("Expected Export command"),
+        }
+    }
+
+    #[test]
+    fn test_import_command() {
+        let cli = Cli::try_parse_from(["bredcrumb", "import", "vectors.txt"]).unwrap();
+        match cli.command {
+            Commands::Import { input } => {
+                assert_eq!(input, PathBuf::from("vectors.txt"));
+            }
+            _ => panic!("Expected Import command"),
+        }
+    }
+
+    #[test]
+    fn test_scan_command() {
+        let cli = Cli::try_parse_from(["bredcrumb", "scan", "./samples", "--wide"]).unwrap();
+        match cli.command {
+            Commands::Scan { path, tag, json, wide } => {
+                assert_eq!(path, PathBuf::from("./samples"));
+                assert!(tag.is_none());
+                assert!(!json);
+                assert!(wide);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_scan_command_with_tag() {
+        let cli =
+            Cli::try_parse_from(["bredcrumb", "scan", "./samples", "--tag", "campaign-1"])
+                .unwrap();
+        match cli.command {
+            Commands::Scan { tag, .. } => {
+                assert_eq!(tag, Some("campaign-1".to_string()));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_batch_command() {
+        let cli = Cli::try_parse_from(["bredcrumb", "batch", "workload.json"]).unwrap();
+        match cli.command {
+            Commands::Batch { manifest, report } => {
+                assert_eq!(manifest, PathBuf::from("workload.json"));
+                assert!(report.is_none());
+            }
+            _ => panic!("Expected Batch command"),
+        }
+    }
+
+    #[test]
+    fn test_batch_with_report() {
+        let cli = Cli::try_parse_from([
+            "bredcrumb",
+            "batch",
+            "workload.json",
+            "--report",
+            "results.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Batch { report, .. } => {
+                assert_eq!(report, Some(PathBuf::from("results.json")));
+            }
+            _ => panic!("Expected Batch command"),
+        }
+    }
+
+    #[test]
+    fn test_completions_command() {
+        let cli = Cli::try_parse_from(["bredcrumb", "completions", "zsh"]).unwrap();
+        match cli.command {
+            Commands::Completions { shell, man } => {
+                assert!(matches!(shell, Some(clap_complete::Shell::Zsh)));
+                assert!(!man);
+            }
+            _ => panic!("Expected Completions command"),
+        }
+    }
+
+    #[test]
+    fn test_completions_man() {
+        let cli = Cli::try_parse_from(["bredcrumb", "completions", "--man"]).unwrap();
+        match cli.command {
+            Commands::Completions { shell, man } => {
+                assert_eq!(shell, None);
+                assert!(man);
+            }
+            _ => panic!("Expected Completions command"),
+        }
+    }
+
+    #[test]
+    fn test_completions_requires_shell_or_man() {
+        let result = Cli::try_parse_from(["bredcrumb", "completions"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_verbose_flag() {
         let cli = Cli::try_parse_from(["bredcrumb", "-v", "list"]).unwrap();
@@ -322,7 +949,19 @@ mod tests {
 
     #[test]
     fn test_all_languages() {
-        for lang in ["c", "cpp", "go", "rust", "csharp", "java"] {
+        for lang in [
+            "c",
+            "cpp",
+            "python",
+            "go",
+            "rust",
+            "csharp",
+            "javascript",
+            "powershell",
+            "java",
+            "elf-object",
+            "coff-object",
+        ] {
             let cli = Cli::try_parse_from(["bredcrumb", "code", "TEST", "-l", lang]).unwrap();
             match cli.command {
                 Commands::Code { .. } => {}
@@ -356,4 +995,11 @@ mod tests {
         assert_eq!(format!("{}", PatchStrategy::Cave), "cave");
         assert_eq!(format!("{}", PatchStrategy::Overlay), "overlay");
     }
+
+    #[test]
+    fn test_encoding_display() {
+        assert_eq!(format!("{}", Encoding::None), "none");
+        assert_eq!(format!("{}", Encoding::Xor), "xor");
+        assert_eq!(format!("{}", Encoding::Stack), "stack");
+    }
 }
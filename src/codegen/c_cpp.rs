@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct CCodeGenerator {
@@ -28,7 +28,7 @@ impl CodeGenerator for CCodeGenerator {
             writeln!(
                 code,
                 "static volatile const char TRACKING_STRING[] = \"{}\";",
-                escape_c_string(string)
+                EscapeProfile::C.escape(string)
             )
             .unwrap();
             writeln!(code).unwrap();
@@ -55,7 +55,7 @@ impl CodeGenerator for CCodeGenerator {
             writeln!(
                 code,
                 "static volatile const char TRACKING_STRING[] = \"{}\";",
-                escape_c_string(string)
+                EscapeProfile::C.escape(string)
             )
             .unwrap();
             writeln!(code).unwrap();
@@ -73,19 +73,182 @@ impl CodeGenerator for CCodeGenerator {
 
         code
     }
-}
 
-fn escape_c_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            '\0' => "\\0".to_string(),
-            _ if c.is_ascii_graphic() || c == ' ' => c.to_string(),
-            _ => format!("\\x{:02X}", c as u8),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(code, "#include <stddef.h>").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "/* Tracking string - DO NOT REMOVE */").unwrap();
+        writeln!(
+            code,
+            "/* Yaz0-encoded and decoded at startup to defeat naive `strings` scans */"
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(
+            code,
+            "static const unsigned char ENCODED_DATA[] = {{ {} }};",
+            bytes
+        )
+        .unwrap();
+        writeln!(code, "static const size_t ENCODED_LEN = {};", encoded_len).unwrap();
+        writeln!(code, "static const size_t DECODED_LEN = {};", decoded_len).unwrap();
+        writeln!(
+            code,
+            "static volatile char TRACKING_STRING[{}];",
+            decoded_len + 1
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "__attribute__((constructor, used))").unwrap();
+        writeln!(code, "static void _tracking_init(void) {{").unwrap();
+        writeln!(code, "    size_t i = 0, out = 0;").unwrap();
+        writeln!(code, "    while (out < DECODED_LEN && i < ENCODED_LEN) {{").unwrap();
+        writeln!(code, "        unsigned char control = ENCODED_DATA[i++];").unwrap();
+        writeln!(
+            code,
+            "        for (int bit = 7; bit >= 0 && out < DECODED_LEN && i < ENCODED_LEN; bit--) {{"
+        )
+        .unwrap();
+        writeln!(code, "            if ((control >> bit) & 1) {{").unwrap();
+        writeln!(
+            code,
+            "                TRACKING_STRING[out++] = (char)ENCODED_DATA[i++];"
+        )
+        .unwrap();
+        writeln!(code, "            }} else {{").unwrap();
+        writeln!(code, "                unsigned char b0 = ENCODED_DATA[i];").unwrap();
+        writeln!(code, "                unsigned char b1 = ENCODED_DATA[i + 1];").unwrap();
+        writeln!(code, "                i += 2;").unwrap();
+        writeln!(
+            code,
+            "                size_t distance = (size_t)(((b0 & 0x0F) << 8) | b1) + 1;"
+        )
+        .unwrap();
+        writeln!(code, "                size_t length;").unwrap();
+        writeln!(code, "                if ((b0 >> 4) == 0) {{").unwrap();
+        writeln!(code, "                    length = ENCODED_DATA[i++] + 0x12;").unwrap();
+        writeln!(code, "                }} else {{").unwrap();
+        writeln!(code, "                    length = (size_t)(b0 >> 4) + 2;").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "                size_t start = out - distance;").unwrap();
+        writeln!(code, "                for (size_t k = 0; k < length; k++) {{").unwrap();
+        writeln!(
+            code,
+            "                    TRACKING_STRING[out] = TRACKING_STRING[start + k];"
+        )
+        .unwrap();
+        writeln!(code, "                    out++;").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "    TRACKING_STRING[DECODED_LEN] = '\\0';").unwrap();
+        writeln!(code, "}}").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        let comment = if self.use_cpp { "//" } else { "/*" };
+        let comment_end = if self.use_cpp { "" } else { " */" };
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(code, "#include <stddef.h>").unwrap();
+                writeln!(code).unwrap();
+                writeln!(
+                    code,
+                    "{} Tracking string is XOR-obfuscated so it doesn't sit in the binary as one literal{}",
+                    comment, comment_end
+                )
+                .unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "static const unsigned char XOR_KEY = 0x{:02X};", key).unwrap();
+                writeln!(
+                    code,
+                    "static const unsigned char XORED[] = {{ {} }};",
+                    bytes
+                )
+                .unwrap();
+                writeln!(
+                    code,
+                    "static volatile char TRACKING_STRING[{}];",
+                    xored.len() + 1
+                )
+                .unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "__attribute__((constructor, used))").unwrap();
+                writeln!(
+                    code,
+                    "static void _tracking_init({}) {{",
+                    if self.use_cpp { "" } else { "void" }
+                )
+                .unwrap();
+                writeln!(
+                    code,
+                    "    for (size_t i = 0; i < sizeof(XORED); i++) {{"
+                )
+                .unwrap();
+                writeln!(
+                    code,
+                    "        TRACKING_STRING[i] = (char)(XORED[i] ^ XOR_KEY);"
+                )
+                .unwrap();
+                writeln!(code, "    }}").unwrap();
+                writeln!(code, "    TRACKING_STRING[sizeof(XORED)] = '\\0';").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "{} Tracking string is built one character at a time, so no constant{}",
+                    comment, comment_end
+                )
+                .unwrap();
+                if !self.use_cpp {
+                    writeln!(code, "/* string literal exists in the binary. */").unwrap();
+                } else {
+                    writeln!(code, "// string literal exists in the binary.").unwrap();
+                }
+                writeln!(code).unwrap();
+                writeln!(code, "__attribute__((constructor, used))").unwrap();
+                writeln!(
+                    code,
+                    "static void _tracking_init({}) {{",
+                    if self.use_cpp { "" } else { "void" }
+                )
+                .unwrap();
+                writeln!(
+                    code,
+                    "    volatile char buf[{}];",
+                    string.len() + 1
+                )
+                .unwrap();
+                for (i, b) in string.bytes().enumerate() {
+                    writeln!(code, "    buf[{}] = (char)0x{:02X};", i, b).unwrap();
+                }
+                writeln!(code, "    buf[{}] = '\\0';", string.len()).unwrap();
+                writeln!(code, "    (void)buf;").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+        }
+    }
 }
+
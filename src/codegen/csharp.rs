@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct CSharpCodeGenerator;
@@ -21,7 +21,7 @@ impl CodeGenerator for CSharpCodeGenerator {
         writeln!(
             code,
             "    public static readonly string Value = \"{}\";",
-            escape_csharp_string(string)
+            EscapeProfile::Csharp.escape(string)
         )
         .unwrap();
         writeln!(code).unwrap();
@@ -35,18 +35,176 @@ impl CodeGenerator for CSharpCodeGenerator {
 
         code
     }
-}
 
-fn escape_csharp_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            '\0' => "\\0".to_string(),
-            _ => c.to_string(),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(code, "// Tracking string - DO NOT REMOVE").unwrap();
+        writeln!(
+            code,
+            "// Yaz0-encoded and decoded once to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "using System.Runtime.CompilerServices;").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "public static class TrackingString").unwrap();
+        writeln!(code, "{{").unwrap();
+        writeln!(
+            code,
+            "    private static readonly byte[] EncodedData = {{ {} }};",
+            bytes
+        )
+        .unwrap();
+        writeln!(code, "    private const int DecodedLen = {};", decoded_len).unwrap();
+        writeln!(code, "    public static readonly string Value = Decode();").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "    private static string Decode()").unwrap();
+        writeln!(code, "    {{").unwrap();
+        writeln!(code, "        var output = new byte[DecodedLen];").unwrap();
+        writeln!(code, "        int i = 0, outLen = 0;").unwrap();
+        writeln!(
+            code,
+            "        while (outLen < DecodedLen && i < EncodedData.Length)"
+        )
+        .unwrap();
+        writeln!(code, "        {{").unwrap();
+        writeln!(code, "            byte control = EncodedData[i++];").unwrap();
+        writeln!(
+            code,
+            "            for (int bit = 7; bit >= 0 && outLen < DecodedLen && i < EncodedData.Length; bit--)"
+        )
+        .unwrap();
+        writeln!(code, "            {{").unwrap();
+        writeln!(code, "                if (((control >> bit) & 1) == 1)").unwrap();
+        writeln!(code, "                {{").unwrap();
+        writeln!(code, "                    output[outLen++] = EncodedData[i++];").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "                else").unwrap();
+        writeln!(code, "                {{").unwrap();
+        writeln!(
+            code,
+            "                    byte b0 = EncodedData[i], b1 = EncodedData[i + 1];"
+        )
+        .unwrap();
+        writeln!(code, "                    i += 2;").unwrap();
+        writeln!(
+            code,
+            "                    int distance = (((b0 & 0x0F) << 8) | b1) + 1;"
+        )
+        .unwrap();
+        writeln!(code, "                    int length;").unwrap();
+        writeln!(code, "                    if ((b0 >> 4) == 0)").unwrap();
+        writeln!(code, "                    {{").unwrap();
+        writeln!(code, "                        length = EncodedData[i++] + 0x12;").unwrap();
+        writeln!(code, "                    }}").unwrap();
+        writeln!(code, "                    else").unwrap();
+        writeln!(code, "                    {{").unwrap();
+        writeln!(code, "                        length = (b0 >> 4) + 2;").unwrap();
+        writeln!(code, "                    }}").unwrap();
+        writeln!(code, "                    int start = outLen - distance;").unwrap();
+        writeln!(code, "                    for (int k = 0; k < length; k++)").unwrap();
+        writeln!(code, "                    {{").unwrap();
+        writeln!(code, "                        output[outLen] = output[start + k];").unwrap();
+        writeln!(code, "                        outLen++;").unwrap();
+        writeln!(code, "                    }}").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(
+            code,
+            "        return System.Text.Encoding.UTF8.GetString(output, 0, DecodedLen);"
+        )
+        .unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "}}").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is XOR-obfuscated so it doesn't sit in the assembly as one literal"
+                )
+                .unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "public static class TrackingString").unwrap();
+                writeln!(code, "{{").unwrap();
+                writeln!(code, "    private const byte XorKey = 0x{:02X};", key).unwrap();
+                writeln!(
+                    code,
+                    "    private static readonly byte[] Xored = {{ {} }};",
+                    bytes
+                )
+                .unwrap();
+                writeln!(code, "    public static readonly string Value = Decode();").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "    private static string Decode()").unwrap();
+                writeln!(code, "    {{").unwrap();
+                writeln!(code, "        var output = new byte[Xored.Length];").unwrap();
+                writeln!(code, "        for (int i = 0; i < Xored.Length; i++)").unwrap();
+                writeln!(code, "        {{").unwrap();
+                writeln!(code, "            output[i] = (byte)(Xored[i] ^ XorKey);").unwrap();
+                writeln!(code, "        }}").unwrap();
+                writeln!(
+                    code,
+                    "        return System.Text.Encoding.UTF8.GetString(output);"
+                )
+                .unwrap();
+                writeln!(code, "    }}").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "// string literal exists in the assembly.").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "public static class TrackingString").unwrap();
+                writeln!(code, "{{").unwrap();
+                writeln!(code, "    public static readonly string Value = Build();").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "    private static string Build()").unwrap();
+                writeln!(code, "    {{").unwrap();
+                writeln!(
+                    code,
+                    "        Span<byte> bytes = stackalloc byte[{}];",
+                    string.len()
+                )
+                .unwrap();
+                for (i, b) in string.bytes().enumerate() {
+                    writeln!(code, "        bytes[{}] = 0x{:02X};", i, b).unwrap();
+                }
+                writeln!(
+                    code,
+                    "        return System.Text.Encoding.UTF8.GetString(bytes);"
+                )
+                .unwrap();
+                writeln!(code, "    }}").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+        }
+    }
 }
+
@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct GoCodeGenerator;
@@ -20,7 +20,7 @@ impl CodeGenerator for GoCodeGenerator {
         writeln!(
             code,
             "var trackingString = \"{}\"",
-            escape_go_string(string)
+            EscapeProfile::Go.escape(string)
         )
         .unwrap();
         writeln!(code).unwrap();
@@ -31,17 +31,125 @@ impl CodeGenerator for GoCodeGenerator {
 
         code
     }
-}
 
-fn escape_go_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            _ => c.to_string(),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(code, "package main").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "// Tracking string - DO NOT REMOVE").unwrap();
+        writeln!(
+            code,
+            "// Yaz0-encoded and decoded at init() to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "var encodedData = []byte{{ {} }}", bytes).unwrap();
+        writeln!(code, "const decodedLen = {}", decoded_len).unwrap();
+        writeln!(code, "var trackingString string").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "func init() {{").unwrap();
+        writeln!(code, "\tout := make([]byte, 0, decodedLen)").unwrap();
+        writeln!(code, "\ti := 0").unwrap();
+        writeln!(code, "\tfor len(out) < decodedLen && i < len(encodedData) {{").unwrap();
+        writeln!(code, "\t\tcontrol := encodedData[i]").unwrap();
+        writeln!(code, "\t\ti++").unwrap();
+        writeln!(
+            code,
+            "\t\tfor bit := 7; bit >= 0 && len(out) < decodedLen && i < len(encodedData); bit-- {{"
+        )
+        .unwrap();
+        writeln!(code, "\t\t\tif (control>>uint(bit))&1 == 1 {{").unwrap();
+        writeln!(code, "\t\t\t\tout = append(out, encodedData[i])").unwrap();
+        writeln!(code, "\t\t\t\ti++").unwrap();
+        writeln!(code, "\t\t\t}} else {{").unwrap();
+        writeln!(code, "\t\t\t\tb0, b1 := encodedData[i], encodedData[i+1]").unwrap();
+        writeln!(code, "\t\t\t\ti += 2").unwrap();
+        writeln!(
+            code,
+            "\t\t\t\tdistance := int((uint16(b0&0x0F)<<8)|uint16(b1)) + 1"
+        )
+        .unwrap();
+        writeln!(code, "\t\t\t\tvar length int").unwrap();
+        writeln!(code, "\t\t\t\tif b0>>4 == 0 {{").unwrap();
+        writeln!(code, "\t\t\t\t\tlength = int(encodedData[i]) + 0x12").unwrap();
+        writeln!(code, "\t\t\t\t\ti++").unwrap();
+        writeln!(code, "\t\t\t\t}} else {{").unwrap();
+        writeln!(code, "\t\t\t\t\tlength = int(b0>>4) + 2").unwrap();
+        writeln!(code, "\t\t\t\t}}").unwrap();
+        writeln!(code, "\t\t\t\tstart := len(out) - distance").unwrap();
+        writeln!(code, "\t\t\t\tfor k := 0; k < length; k++ {{").unwrap();
+        writeln!(code, "\t\t\t\t\tout = append(out, out[start+k])").unwrap();
+        writeln!(code, "\t\t\t\t}}").unwrap();
+        writeln!(code, "\t\t\t}}").unwrap();
+        writeln!(code, "\t\t}}").unwrap();
+        writeln!(code, "\t}}").unwrap();
+        writeln!(code, "\ttrackingString = string(out)").unwrap();
+        writeln!(code, "}}").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(code, "package main").unwrap();
+                writeln!(code).unwrap();
+                writeln!(
+                    code,
+                    "// Tracking string is XOR-obfuscated so it doesn't sit in the binary as one literal"
+                )
+                .unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "const xorKey = byte(0x{:02X})", key).unwrap();
+                writeln!(code, "var xored = []byte{{ {} }}", bytes).unwrap();
+                writeln!(code, "var trackingString string").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "func init() {{").unwrap();
+                writeln!(code, "\tout := make([]byte, len(xored))").unwrap();
+                writeln!(code, "\tfor i, b := range xored {{").unwrap();
+                writeln!(code, "\t\tout[i] = b ^ xorKey").unwrap();
+                writeln!(code, "\t}}").unwrap();
+                writeln!(code, "\ttrackingString = string(out)").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(code, "package main").unwrap();
+                writeln!(code).unwrap();
+                writeln!(
+                    code,
+                    "// Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "// string literal exists in the binary.").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "var trackingString string").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "func init() {{").unwrap();
+                writeln!(code, "\tvar buf [{}]byte", string.len()).unwrap();
+                for (i, b) in string.bytes().enumerate() {
+                    writeln!(code, "\tbuf[{}] = 0x{:02X}", i, b).unwrap();
+                }
+                writeln!(code, "\ttrackingString = string(buf[:])").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+        }
+    }
 }
+
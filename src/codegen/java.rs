@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list_signed, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct JavaCodeGenerator;
@@ -18,7 +18,7 @@ impl CodeGenerator for JavaCodeGenerator {
         writeln!(
             code,
             "    public static final String VALUE = \"{}\";",
-            escape_java_string(string)
+            EscapeProfile::Java.escape(string)
         )
         .unwrap();
         writeln!(code).unwrap();
@@ -35,18 +35,157 @@ impl CodeGenerator for JavaCodeGenerator {
 
         code
     }
-}
 
-fn escape_java_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            '\0' => "\\0".to_string(),
-            _ => c.to_string(),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, encoded_len, decoded_len) = encoded_byte_list_signed(string);
+        let mut code = String::new();
+
+        writeln!(code, "// Tracking string - DO NOT REMOVE").unwrap();
+        writeln!(
+            code,
+            "// Yaz0-encoded and decoded once to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "public class TrackingString {{").unwrap();
+        writeln!(
+            code,
+            "    private static final byte[] ENCODED_DATA = {{ {} }};",
+            bytes
+        )
+        .unwrap();
+        writeln!(code, "    private static final int DECODED_LEN = {};", decoded_len).unwrap();
+        writeln!(code, "    public static final String VALUE = decode();").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "    private static String decode() {{").unwrap();
+        writeln!(code, "        byte[] output = new byte[DECODED_LEN];").unwrap();
+        writeln!(code, "        int i = 0, outLen = 0;").unwrap();
+        writeln!(
+            code,
+            "        while (outLen < DECODED_LEN && i < ENCODED_DATA.length) {{"
+        )
+        .unwrap();
+        writeln!(code, "            int control = ENCODED_DATA[i++] & 0xFF;").unwrap();
+        writeln!(
+            code,
+            "            for (int bit = 7; bit >= 0 && outLen < DECODED_LEN && i < ENCODED_DATA.length; bit--) {{"
+        )
+        .unwrap();
+        writeln!(code, "                if (((control >> bit) & 1) == 1) {{").unwrap();
+        writeln!(code, "                    output[outLen++] = ENCODED_DATA[i++];").unwrap();
+        writeln!(code, "                }} else {{").unwrap();
+        writeln!(
+            code,
+            "                    int b0 = ENCODED_DATA[i] & 0xFF, b1 = ENCODED_DATA[i + 1] & 0xFF;"
+        )
+        .unwrap();
+        writeln!(code, "                    i += 2;").unwrap();
+        writeln!(
+            code,
+            "                    int distance = (((b0 & 0x0F) << 8) | b1) + 1;"
+        )
+        .unwrap();
+        writeln!(code, "                    int length;").unwrap();
+        writeln!(code, "                    if ((b0 >> 4) == 0) {{").unwrap();
+        writeln!(
+            code,
+            "                        length = (ENCODED_DATA[i++] & 0xFF) + 0x12;"
+        )
+        .unwrap();
+        writeln!(code, "                    }} else {{").unwrap();
+        writeln!(code, "                        length = (b0 >> 4) + 2;").unwrap();
+        writeln!(code, "                    }}").unwrap();
+        writeln!(code, "                    int start = outLen - distance;").unwrap();
+        writeln!(code, "                    for (int k = 0; k < length; k++) {{").unwrap();
+        writeln!(code, "                        output[outLen] = output[start + k];").unwrap();
+        writeln!(code, "                        outLen++;").unwrap();
+        writeln!(code, "                    }}").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(
+            code,
+            "        return new String(output, java.nio.charset.StandardCharsets.UTF_8);"
+        )
+        .unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "}}").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("(byte)0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is XOR-obfuscated so it doesn't sit in the class file as one literal"
+                )
+                .unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "public class TrackingString {{").unwrap();
+                writeln!(code, "    private static final byte XOR_KEY = (byte)0x{:02X};", key).unwrap();
+                writeln!(
+                    code,
+                    "    private static final byte[] XORED = {{ {} }};",
+                    bytes
+                )
+                .unwrap();
+                writeln!(code, "    public static final String VALUE = decode();").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "    private static String decode() {{").unwrap();
+                writeln!(code, "        byte[] output = new byte[XORED.length];").unwrap();
+                writeln!(code, "        for (int i = 0; i < XORED.length; i++) {{").unwrap();
+                writeln!(code, "            output[i] = (byte)(XORED[i] ^ XOR_KEY);").unwrap();
+                writeln!(code, "        }}").unwrap();
+                writeln!(
+                    code,
+                    "        return new String(output, java.nio.charset.StandardCharsets.UTF_8);"
+                )
+                .unwrap();
+                writeln!(code, "    }}").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "// string literal exists in the class file.").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "public class TrackingString {{").unwrap();
+                writeln!(code, "    public static final String VALUE = build();").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "    private static String build() {{").unwrap();
+                writeln!(code, "        byte[] bytes = new byte[{}];", string.len()).unwrap();
+                for (i, b) in string.bytes().enumerate() {
+                    writeln!(code, "        bytes[{}] = (byte)0x{:02X};", i, b).unwrap();
+                }
+                writeln!(
+                    code,
+                    "        return new String(bytes, java.nio.charset.StandardCharsets.UTF_8);"
+                )
+                .unwrap();
+                writeln!(code, "    }}").unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+        }
+    }
 }
+
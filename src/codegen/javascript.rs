@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct JavaScriptCodeGenerator;
@@ -11,7 +11,7 @@ impl CodeGenerator for JavaScriptCodeGenerator {
         writeln!(
             code,
             "const TRACKING_STRING = \"{}\";",
-            escape_js_string(string)
+            EscapeProfile::JavaScript.escape(string)
         )
         .unwrap();
         writeln!(code, "const TRACKING_STRING_LEN = {};", string.len()).unwrap();
@@ -25,18 +25,118 @@ impl CodeGenerator for JavaScriptCodeGenerator {
 
         code
     }
-}
 
-fn escape_js_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            '\0' => "\\0".to_string(),
-            _ => c.to_string(),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, _encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(
+            code,
+            "// Yaz0-encoded and decoded at load time to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code, "const ENCODED_DATA = [{}];", bytes).unwrap();
+        writeln!(code, "const DECODED_LEN = {};", decoded_len).unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "function decodeTrackingString() {{").unwrap();
+        writeln!(code, "    const out = [];").unwrap();
+        writeln!(code, "    let i = 0;").unwrap();
+        writeln!(
+            code,
+            "    while (out.length < DECODED_LEN && i < ENCODED_DATA.length) {{"
+        )
+        .unwrap();
+        writeln!(code, "        const control = ENCODED_DATA[i++];").unwrap();
+        writeln!(
+            code,
+            "        for (let bit = 7; bit >= 0 && out.length < DECODED_LEN && i < ENCODED_DATA.length; bit--) {{"
+        )
+        .unwrap();
+        writeln!(code, "            if ((control >> bit) & 1) {{").unwrap();
+        writeln!(code, "                out.push(ENCODED_DATA[i++]);").unwrap();
+        writeln!(code, "            }} else {{").unwrap();
+        writeln!(code, "                const b0 = ENCODED_DATA[i], b1 = ENCODED_DATA[i + 1];").unwrap();
+        writeln!(code, "                i += 2;").unwrap();
+        writeln!(
+            code,
+            "                const distance = (((b0 & 0x0F) << 8) | b1) + 1;"
+        )
+        .unwrap();
+        writeln!(code, "                let length;").unwrap();
+        writeln!(code, "                if ((b0 >> 4) === 0) {{").unwrap();
+        writeln!(code, "                    length = ENCODED_DATA[i++] + 0x12;").unwrap();
+        writeln!(code, "                }} else {{").unwrap();
+        writeln!(code, "                    length = (b0 >> 4) + 2;").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "                const start = out.length - distance;").unwrap();
+        writeln!(code, "                for (let k = 0; k < length; k++) {{").unwrap();
+        writeln!(code, "                    out.push(out[start + k]);").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(
+            code,
+            "    return Buffer.from(out).toString(\"utf-8\");"
+        )
+        .unwrap();
+        writeln!(code, "}}").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "const TRACKING_STRING = decodeTrackingString();").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is XOR-obfuscated so it doesn't sit in the file as one literal"
+                )
+                .unwrap();
+                writeln!(code, "const XOR_KEY = 0x{:02X};", key).unwrap();
+                writeln!(code, "const XORED = [{}];", bytes).unwrap();
+                writeln!(
+                    code,
+                    "const TRACKING_STRING = Buffer.from(XORED.map(b => b ^ XOR_KEY)).toString(\"utf-8\");"
+                )
+                .unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "// string literal exists in the source.").unwrap();
+                writeln!(code, "const chars = new Array({});", string.chars().count()).unwrap();
+                for (i, ch) in string.chars().enumerate() {
+                    writeln!(
+                        code,
+                        "chars[{}] = \"{}\";",
+                        i,
+                        EscapeProfile::JavaScript.escape(&ch.to_string())
+                    )
+                    .unwrap();
+                }
+                writeln!(code, "const TRACKING_STRING = chars.join(\"\");").unwrap();
+
+                code
+            }
+        }
+    }
 }
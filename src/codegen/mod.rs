@@ -2,16 +2,114 @@ pub mod c_cpp;
 pub mod csharp;
 pub mod go;
 pub mod java;
+pub mod javascript;
+pub mod object;
+pub mod powershell;
+pub mod python;
 pub mod rust;
+pub mod template;
+pub mod yaz0;
 
 pub use c_cpp::CCodeGenerator;
 pub use csharp::CSharpCodeGenerator;
 pub use go::GoCodeGenerator;
 pub use java::JavaCodeGenerator;
+pub use javascript::JavaScriptCodeGenerator;
+pub use object::ObjectCodeGenerator;
+pub use powershell::PowerShellCodeGenerator;
+pub use python::PythonCodeGenerator;
 pub use rust::RustCodeGenerator;
+pub use template::{
+    load_generators, select_generator, EscapeProfile, GeneratorEntry, TemplateCodeGenerator,
+};
+
+/// How the embedded breadcrumb should reconstruct itself at runtime so it
+/// doesn't sit in the binary's data section as one contiguous literal that
+/// `strings`/naive YARA dumps would catch whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObfuscationMode {
+    /// Keep the current literal (or, under `generate_encoded`, the
+    /// Yaz0-compressed byte array).
+    None,
+    /// XOR every byte with a single random key byte, stored as a byte
+    /// array alongside the key and decoded by a loop at startup.
+    Xor,
+    /// Assign each character into successive elements of a local buffer
+    /// one at a time, so no constant string literal exists at all.
+    Stack,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<crate::cli::Encoding> for ObfuscationMode {
+    fn from(e: crate::cli::Encoding) -> Self {
+        match e {
+            crate::cli::Encoding::None => ObfuscationMode::None,
+            crate::cli::Encoding::Xor => ObfuscationMode::Xor,
+            crate::cli::Encoding::Stack => ObfuscationMode::Stack,
+        }
+    }
+}
 
 pub trait CodeGenerator {
     fn generate(&self, string: &str) -> String;
+
+    /// Generate a snippet that stores the payload Yaz0-compressed and
+    /// reconstructs it at init/constructor time, defeating naive `strings`
+    /// scans while keeping the bytes present for attribution.
+    fn generate_encoded(&self, string: &str) -> String;
+
+    /// Generate a snippet that reconstructs the payload at runtime per
+    /// `mode` instead of embedding it as a literal. This default ignores
+    /// `mode` and returns the plain literal from [`CodeGenerator::generate`];
+    /// generators without an obfuscated form (user `--template` targets)
+    /// keep it.
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor | ObfuscationMode::Stack => self.generate(string),
+        }
+    }
+}
+
+/// XOR every byte of `string` with a single random key byte, returning the
+/// key and the XOR'd bytes. Shared by every per-language `Xor`
+/// implementation of [`CodeGenerator::generate_obfuscated`].
+pub(crate) fn xor_encode(string: &str) -> (u8, Vec<u8>) {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    // A key of 0 would XOR to a no-op, which would put the plaintext right
+    // back in the data section, so pick from 1..=255.
+    let key: u8 = rng.gen_range(1..=255);
+    let xored = string.bytes().map(|b| b ^ key).collect();
+    (key, xored)
+}
+
+/// Yaz0-compress `string` and format the result as a `0x..`-separated byte
+/// list suitable for embedding as a language-native byte array literal.
+/// Shared by every per-language `generate_encoded` implementation so the
+/// encoding logic isn't duplicated across generators.
+pub(crate) fn encoded_byte_list(string: &str) -> (String, usize, usize) {
+    let bytes = string.as_bytes();
+    let compressed = yaz0::encode(bytes);
+    let list = compressed
+        .iter()
+        .map(|b| format!("0x{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(", ");
+    (list, compressed.len(), bytes.len())
+}
+
+/// Same as `encoded_byte_list`, but casts each element to `(byte)` for
+/// languages (Java) whose `byte` type is signed and rejects literals > 0x7F.
+pub(crate) fn encoded_byte_list_signed(string: &str) -> (String, usize, usize) {
+    let bytes = string.as_bytes();
+    let compressed = yaz0::encode(bytes);
+    let list = compressed
+        .iter()
+        .map(|b| format!("(byte)0x{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(", ");
+    (list, compressed.len(), bytes.len())
 }
 
 #[cfg(test)]
@@ -99,6 +197,65 @@ mod tests {
         assert!(code.contains("TrackingString"));
     }
 
+    #[test]
+    fn test_c_generator_encoded_hides_literal() {
+        let gen = CCodeGenerator::new(false);
+        let code = gen.generate_encoded("TEST123");
+        assert!(code.contains("ENCODED_DATA"));
+        assert!(!code.contains("\"TEST123\""));
+    }
+
+    #[test]
+    fn test_rust_generator_encoded_hides_literal() {
+        let code = RustCodeGenerator.generate_encoded("TEST123");
+        assert!(code.contains("ENCODED_DATA"));
+        assert!(!code.contains("b\"TEST123\""));
+    }
+
+    #[test]
+    fn test_java_generator_encoded_signed_cast() {
+        let code = JavaCodeGenerator.generate_encoded("TEST123");
+        assert!(code.contains("(byte)0xFF"));
+    }
+
+    #[test]
+    fn test_object_generator_elf_has_magic_and_symbol() {
+        let obj = ObjectCodeGenerator.generate_elf("TEST123");
+        assert_eq!(&obj[0..4], &[0x7f, b'E', b'L', b'F']);
+        assert!(obj.windows(7).any(|w| w == b"TEST123"));
+        assert!(obj.windows(17).any(|w| w == b"g_tracking_string"));
+    }
+
+    #[test]
+    fn test_object_generator_coff_has_symbol() {
+        let obj = ObjectCodeGenerator.generate_coff("TEST123");
+        assert_eq!(&obj[0..2], &0x8664u16.to_le_bytes());
+        assert!(obj.windows(7).any(|w| w == b"TEST123"));
+        assert!(obj.windows(17).any(|w| w == b"g_tracking_string"));
+    }
+
+    #[test]
+    fn test_object_generator_elf_symbol_size_excludes_terminator() {
+        // st_size should cover just the string, not the NUL the section adds
+        // for C-style consumers, so a linker-side strlen() lands correctly.
+        const EHSIZE: usize = 64;
+        let obj = ObjectCodeGenerator.generate_elf("TEST123");
+        let rodata_len = "TEST123".len() + 1; // + NUL terminator
+        let symtab_off = (EHSIZE + rodata_len + 7) & !7; // align_up(.., 8)
+        let st_size_off = symtab_off + 24 + 16; // 2nd Elf64_Sym, st_size field
+        let st_size = u64::from_le_bytes(obj[st_size_off..st_size_off + 8].try_into().unwrap());
+        assert_eq!(st_size, "TEST123".len() as u64);
+    }
+
+    #[test]
+    fn test_object_generator_coff_section_includes_terminator() {
+        let obj = ObjectCodeGenerator.generate_coff("TEST123");
+        // IMAGE_SECTION_HEADER follows the 20-byte file header: Name[8],
+        // VirtualSize[4], VirtualAddress[4], then SizeOfRawData[4] at 36..40.
+        let size_of_raw_data = u32::from_le_bytes(obj[36..40].try_into().unwrap());
+        assert_eq!(size_of_raw_data, "TEST123".len() as u32 + 1);
+    }
+
     #[test]
     fn test_all_generators_produce_output() {
         let generators: Vec<Box<dyn CodeGenerator>> = vec![
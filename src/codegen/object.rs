@@ -0,0 +1,265 @@
+//! Relocatable object-file backend.
+//!
+//! Unlike the per-language generators, this doesn't emit source that still
+//! needs a compiler on the target's build host: it writes the tracking
+//! string straight into a minimal ELF `ET_REL` or COFF object with a defined
+//! symbol, ready to add to any link line as `tracking.o`/`tracking.obj`.
+//! No decode stub or volatile-constructor trick is needed, since the linker
+//! keeps the section simply by the symbol being referenced/exported.
+
+/// Name of the global/external symbol the tracking string is exposed under.
+const SYMBOL_NAME: &str = "g_tracking_string";
+
+pub struct ObjectCodeGenerator;
+
+impl ObjectCodeGenerator {
+    /// Emit a 64-bit little-endian ELF `ET_REL` object containing the string
+    /// in `.rodata`, with a `STB_GLOBAL`/`STT_OBJECT` symbol pointing at it.
+    pub fn generate_elf(&self, string: &str) -> Vec<u8> {
+        elf_object(string.as_bytes())
+    }
+
+    /// Emit an x86-64 COFF object containing the string in `.rdata`, with an
+    /// `IMAGE_SYM_CLASS_EXTERNAL` symbol pointing at it.
+    pub fn generate_coff(&self, string: &str) -> Vec<u8> {
+        coff_object(string.as_bytes())
+    }
+}
+
+/// Growable string table that reserves offset 0 for the empty string, the
+/// convention ELF/COFF string and section-name tables both rely on.
+struct StrTab {
+    data: Vec<u8>,
+}
+
+impl StrTab {
+    fn new() -> Self {
+        Self { data: vec![0] }
+    }
+
+    fn add(&mut self, s: &str) -> u32 {
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(s.as_bytes());
+        self.data.push(0);
+        offset
+    }
+}
+
+fn elf_object(payload: &[u8]) -> Vec<u8> {
+    let mut rodata = payload.to_vec();
+    rodata.push(0); // NUL-terminate for C-style consumers
+
+    let mut shstrtab = StrTab::new();
+    let name_rodata = shstrtab.add(".rodata");
+    let name_symtab = shstrtab.add(".symtab");
+    let name_strtab = shstrtab.add(".strtab");
+    let name_shstrtab = shstrtab.add(".shstrtab");
+
+    let mut strtab = StrTab::new();
+    let sym_name = strtab.add(SYMBOL_NAME);
+
+    let mut symtab = Vec::new();
+    symtab.extend_from_slice(&elf64_sym(0, 0, 0, 0, 0)); // mandatory null symbol
+    symtab.extend_from_slice(&elf64_sym(
+        sym_name,
+        (1 << 4) | 1, // STB_GLOBAL << 4 | STT_OBJECT
+        1,            // st_shndx: .rodata is section index 1
+        0,            // st_value: offset within .rodata
+        payload.len() as u64,
+    ));
+
+    const EHSIZE: usize = 64;
+    const SHENTSIZE: usize = 64;
+    const SHNUM: usize = 5;
+
+    let rodata_off = EHSIZE;
+    let symtab_off = align_up(rodata_off + rodata.len(), 8);
+    let strtab_off = symtab_off + symtab.len();
+    let shstrtab_off = strtab_off + strtab.data.len();
+    let shoff = align_up(shstrtab_off + shstrtab.data.len(), 8);
+
+    let mut out = vec![0u8; shoff + SHNUM * SHENTSIZE];
+
+    out[0..4].copy_from_slice(&[0x7f, b'E', b'L', b'F']);
+    out[4] = 2; // ELFCLASS64
+    out[5] = 1; // ELFDATA2LSB
+    out[6] = 1; // EV_CURRENT
+    out[16..18].copy_from_slice(&1u16.to_le_bytes()); // e_type = ET_REL
+    out[18..20].copy_from_slice(&62u16.to_le_bytes()); // e_machine = EM_X86_64
+    out[20..24].copy_from_slice(&1u32.to_le_bytes()); // e_version
+    out[40..48].copy_from_slice(&(shoff as u64).to_le_bytes()); // e_shoff
+    out[52..54].copy_from_slice(&(EHSIZE as u16).to_le_bytes()); // e_ehsize
+    out[58..60].copy_from_slice(&(SHENTSIZE as u16).to_le_bytes()); // e_shentsize
+    out[60..62].copy_from_slice(&(SHNUM as u16).to_le_bytes()); // e_shnum
+    out[62..64].copy_from_slice(&4u16.to_le_bytes()); // e_shstrndx
+
+    out[rodata_off..rodata_off + rodata.len()].copy_from_slice(&rodata);
+    out[symtab_off..symtab_off + symtab.len()].copy_from_slice(&symtab);
+    out[strtab_off..strtab_off + strtab.data.len()].copy_from_slice(&strtab.data);
+    out[shstrtab_off..shstrtab_off + shstrtab.data.len()].copy_from_slice(&shstrtab.data);
+
+    let headers = [
+        elf64_shdr(0, 0, 0, 0, 0, 0, 0, 0, 0, 0), // SHT_NULL
+        elf64_shdr(
+            name_rodata,
+            1, // SHT_PROGBITS
+            2, // SHF_ALLOC
+            0,
+            rodata_off as u64,
+            rodata.len() as u64,
+            0,
+            0,
+            1,
+            0,
+        ),
+        elf64_shdr(
+            name_symtab,
+            2, // SHT_SYMTAB
+            0,
+            0,
+            symtab_off as u64,
+            symtab.len() as u64,
+            3, // sh_link: .strtab section index
+            1, // sh_info: index of first non-local (global) symbol
+            8,
+            24, // sh_entsize: sizeof(Elf64_Sym)
+        ),
+        elf64_shdr(
+            name_strtab,
+            3, // SHT_STRTAB
+            0,
+            0,
+            strtab_off as u64,
+            strtab.data.len() as u64,
+            0,
+            0,
+            1,
+            0,
+        ),
+        elf64_shdr(
+            name_shstrtab,
+            3, // SHT_STRTAB
+            0,
+            0,
+            shstrtab_off as u64,
+            shstrtab.data.len() as u64,
+            0,
+            0,
+            1,
+            0,
+        ),
+    ];
+    for (i, header) in headers.iter().enumerate() {
+        let off = shoff + i * SHENTSIZE;
+        out[off..off + SHENTSIZE].copy_from_slice(header);
+    }
+
+    out
+}
+
+fn elf64_sym(name: u32, info: u8, shndx: u16, value: u64, size: u64) -> [u8; 24] {
+    let mut sym = [0u8; 24];
+    sym[0..4].copy_from_slice(&name.to_le_bytes());
+    sym[4] = info;
+    sym[5] = 0; // st_other
+    sym[6..8].copy_from_slice(&shndx.to_le_bytes());
+    sym[8..16].copy_from_slice(&value.to_le_bytes());
+    sym[16..24].copy_from_slice(&size.to_le_bytes());
+    sym
+}
+
+#[allow(clippy::too_many_arguments)]
+fn elf64_shdr(
+    name: u32,
+    sh_type: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    addralign: u64,
+    entsize: u64,
+) -> [u8; 64] {
+    let mut shdr = [0u8; 64];
+    shdr[0..4].copy_from_slice(&name.to_le_bytes());
+    shdr[4..8].copy_from_slice(&sh_type.to_le_bytes());
+    shdr[8..16].copy_from_slice(&flags.to_le_bytes());
+    shdr[16..24].copy_from_slice(&addr.to_le_bytes());
+    shdr[24..32].copy_from_slice(&offset.to_le_bytes());
+    shdr[32..40].copy_from_slice(&size.to_le_bytes());
+    shdr[40..44].copy_from_slice(&link.to_le_bytes());
+    shdr[44..48].copy_from_slice(&info.to_le_bytes());
+    shdr[48..56].copy_from_slice(&addralign.to_le_bytes());
+    shdr[56..64].copy_from_slice(&entsize.to_le_bytes());
+    shdr
+}
+
+fn coff_object(payload: &[u8]) -> Vec<u8> {
+    let mut rdata = payload.to_vec();
+    rdata.push(0);
+
+    const FILE_HEADER_SIZE: usize = 20;
+    const SECTION_HEADER_SIZE: usize = 40;
+    const SYMBOL_SIZE: usize = 18;
+
+    let section_data_off = FILE_HEADER_SIZE + SECTION_HEADER_SIZE;
+    let symtab_off = section_data_off + rdata.len();
+
+    let mut string_table = Vec::new();
+    string_table.extend_from_slice(&0u32.to_le_bytes()); // patched below
+    let name_offset = string_table.len() as u32;
+    string_table.extend_from_slice(SYMBOL_NAME.as_bytes());
+    string_table.push(0);
+    let string_table_len = string_table.len() as u32;
+    string_table[0..4].copy_from_slice(&string_table_len.to_le_bytes());
+
+    let mut out = Vec::with_capacity(symtab_off + SYMBOL_SIZE + string_table.len());
+
+    // IMAGE_FILE_HEADER
+    out.extend_from_slice(&0x8664u16.to_le_bytes()); // Machine = IMAGE_FILE_MACHINE_AMD64
+    out.extend_from_slice(&1u16.to_le_bytes()); // NumberOfSections
+    out.extend_from_slice(&0u32.to_le_bytes()); // TimeDateStamp
+    out.extend_from_slice(&(symtab_off as u32).to_le_bytes()); // PointerToSymbolTable
+    out.extend_from_slice(&1u32.to_le_bytes()); // NumberOfSymbols
+    out.extend_from_slice(&0u16.to_le_bytes()); // SizeOfOptionalHeader
+    out.extend_from_slice(&0u16.to_le_bytes()); // Characteristics
+
+    // IMAGE_SECTION_HEADER for .rdata
+    let mut name_field = [0u8; 8];
+    name_field[..6].copy_from_slice(b".rdata");
+    out.extend_from_slice(&name_field);
+    out.extend_from_slice(&0u32.to_le_bytes()); // VirtualSize (unused pre-link)
+    out.extend_from_slice(&0u32.to_le_bytes()); // VirtualAddress
+    out.extend_from_slice(&(rdata.len() as u32).to_le_bytes()); // SizeOfRawData
+    out.extend_from_slice(&(section_data_off as u32).to_le_bytes()); // PointerToRawData
+    out.extend_from_slice(&0u32.to_le_bytes()); // PointerToRelocations
+    out.extend_from_slice(&0u32.to_le_bytes()); // PointerToLinenumbers
+    out.extend_from_slice(&0u16.to_le_bytes()); // NumberOfRelocations
+    out.extend_from_slice(&0u16.to_le_bytes()); // NumberOfLinenumbers
+    let characteristics: u32 = 0x40000040; // IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ
+    out.extend_from_slice(&characteristics.to_le_bytes());
+
+    // .rdata contents
+    out.extend_from_slice(&rdata);
+
+    // IMAGE_SYMBOL
+    out.extend_from_slice(&0u32.to_le_bytes()); // Name[0..4] = 0 marks a string-table reference
+    out.extend_from_slice(&name_offset.to_le_bytes()); // Name[4..8] = string table offset
+    out.extend_from_slice(&0u32.to_le_bytes()); // Value: offset within .rdata
+    out.extend_from_slice(&1i16.to_le_bytes()); // SectionNumber (1-based)
+    out.extend_from_slice(&0u16.to_le_bytes()); // Type
+    out.push(2); // StorageClass = IMAGE_SYM_CLASS_EXTERNAL
+    out.push(0); // NumberOfAuxSymbols
+
+    out.extend_from_slice(&string_table);
+
+    out
+}
+
+fn align_up(value: usize, alignment: usize) -> usize {
+    if alignment == 0 {
+        return value;
+    }
+    (value + alignment - 1) & !(alignment - 1)
+}
@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct PowerShellCodeGenerator;
@@ -8,24 +8,122 @@ impl CodeGenerator for PowerShellCodeGenerator {
         let mut code = String::new();
 
         writeln!(code, "# Tracking string: {}", string).unwrap();
-        writeln!(code, "$TrackingString = \"{}\"", escape_ps_string(string)).unwrap();
+        writeln!(
+            code,
+            "$TrackingString = \"{}\"",
+            EscapeProfile::PowerShell.escape(string)
+        )
+        .unwrap();
         writeln!(code, "$TrackingStringLen = {}", string.len()).unwrap();
 
         code
     }
-}
 
-fn escape_ps_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "`\"".to_string(),
-            '`' => "``".to_string(),
-            '$' => "`$".to_string(),
-            '\n' => "`n".to_string(),
-            '\r' => "`r".to_string(),
-            '\t' => "`t".to_string(),
-            '\0' => "`0".to_string(),
-            _ => c.to_string(),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, _encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(
+            code,
+            "# Yaz0-encoded and decoded at load time to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code, "$EncodedData = @({})", bytes).unwrap();
+        writeln!(code, "$DecodedLen = {}", decoded_len).unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "$out = New-Object System.Collections.Generic.List[byte]").unwrap();
+        writeln!(code, "$i = 0").unwrap();
+        writeln!(code, "while ($out.Count -lt $DecodedLen -and $i -lt $EncodedData.Length) {{").unwrap();
+        writeln!(code, "    $control = $EncodedData[$i]").unwrap();
+        writeln!(code, "    $i++").unwrap();
+        writeln!(
+            code,
+            "    for ($bit = 7; $bit -ge 0 -and $out.Count -lt $DecodedLen -and $i -lt $EncodedData.Length; $bit--) {{"
+        )
+        .unwrap();
+        writeln!(code, "        if (($control -shr $bit) -band 1) {{").unwrap();
+        writeln!(code, "            $out.Add($EncodedData[$i])").unwrap();
+        writeln!(code, "            $i++").unwrap();
+        writeln!(code, "        }} else {{").unwrap();
+        writeln!(code, "            $b0 = $EncodedData[$i]; $b1 = $EncodedData[$i + 1]").unwrap();
+        writeln!(code, "            $i += 2").unwrap();
+        writeln!(
+            code,
+            "            $distance = ((($b0 -band 0x0F) -shl 8) -bor $b1) + 1"
+        )
+        .unwrap();
+        writeln!(code, "            if (($b0 -shr 4) -eq 0) {{").unwrap();
+        writeln!(code, "                $length = $EncodedData[$i] + 0x12").unwrap();
+        writeln!(code, "                $i++").unwrap();
+        writeln!(code, "            }} else {{").unwrap();
+        writeln!(code, "                $length = ($b0 -shr 4) + 2").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "            $start = $out.Count - $distance").unwrap();
+        writeln!(code, "            for ($k = 0; $k -lt $length; $k++) {{").unwrap();
+        writeln!(code, "                $out.Add($out[$start + $k])").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "}}").unwrap();
+        writeln!(
+            code,
+            "$TrackingString = [System.Text.Encoding]::UTF8.GetString($out.ToArray())"
+        )
+        .unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "# Tracking string is XOR-obfuscated so it doesn't sit in the file as one literal"
+                )
+                .unwrap();
+                writeln!(code, "$XorKey = 0x{:02X}", key).unwrap();
+                writeln!(code, "$Xored = @({})", bytes).unwrap();
+                writeln!(
+                    code,
+                    "$TrackingString = [System.Text.Encoding]::UTF8.GetString(($Xored | ForEach-Object {{ $_ -bxor $XorKey }}))"
+                )
+                .unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "# Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "# string literal exists in the script.").unwrap();
+                writeln!(code, "$Chars = New-Object string[] {}", string.chars().count()).unwrap();
+                for (i, ch) in string.chars().enumerate() {
+                    writeln!(
+                        code,
+                        "$Chars[{}] = \"{}\"",
+                        i,
+                        EscapeProfile::PowerShell.escape(&ch.to_string())
+                    )
+                    .unwrap();
+                }
+                writeln!(code, "$TrackingString = -join $Chars").unwrap();
+
+                code
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct PythonCodeGenerator;
@@ -11,24 +11,123 @@ impl CodeGenerator for PythonCodeGenerator {
         writeln!(
             code,
             "TRACKING_STRING = \"{}\"",
-            escape_python_string(string)
+            EscapeProfile::Python.escape(string)
         )
         .unwrap();
         writeln!(code, "TRACKING_STRING_LEN = {}", string.len()).unwrap();
 
         code
     }
-}
 
-fn escape_python_string(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            _ => c.to_string(),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, _encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(
+            code,
+            "# Yaz0-encoded and decoded at import time to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code, "ENCODED_DATA = bytes([{}])", bytes).unwrap();
+        writeln!(code, "DECODED_LEN = {}", decoded_len).unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "def _decode_tracking_string():").unwrap();
+        writeln!(code, "    out = bytearray()").unwrap();
+        writeln!(code, "    i = 0").unwrap();
+        writeln!(
+            code,
+            "    while len(out) < DECODED_LEN and i < len(ENCODED_DATA):"
+        )
+        .unwrap();
+        writeln!(code, "        control = ENCODED_DATA[i]").unwrap();
+        writeln!(code, "        i += 1").unwrap();
+        writeln!(
+            code,
+            "        for bit in range(7, -1, -1):"
+        )
+        .unwrap();
+        writeln!(
+            code,
+            "            if len(out) >= DECODED_LEN or i >= len(ENCODED_DATA):"
+        )
+        .unwrap();
+        writeln!(code, "                break").unwrap();
+        writeln!(code, "            if (control >> bit) & 1:").unwrap();
+        writeln!(code, "                out.append(ENCODED_DATA[i])").unwrap();
+        writeln!(code, "                i += 1").unwrap();
+        writeln!(code, "            else:").unwrap();
+        writeln!(code, "                b0, b1 = ENCODED_DATA[i], ENCODED_DATA[i + 1]").unwrap();
+        writeln!(code, "                i += 2").unwrap();
+        writeln!(
+            code,
+            "                distance = (((b0 & 0x0F) << 8) | b1) + 1"
+        )
+        .unwrap();
+        writeln!(code, "                if (b0 >> 4) == 0:").unwrap();
+        writeln!(code, "                    length = ENCODED_DATA[i] + 0x12").unwrap();
+        writeln!(code, "                    i += 1").unwrap();
+        writeln!(code, "                else:").unwrap();
+        writeln!(code, "                    length = (b0 >> 4) + 2").unwrap();
+        writeln!(code, "                start = len(out) - distance").unwrap();
+        writeln!(code, "                for k in range(length):").unwrap();
+        writeln!(code, "                    out.append(out[start + k])").unwrap();
+        writeln!(code, "    return out.decode(\"utf-8\")").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "TRACKING_STRING = _decode_tracking_string()").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "# Tracking string is XOR-obfuscated so it doesn't sit in the file as one literal"
+                )
+                .unwrap();
+                writeln!(code, "_XOR_KEY = 0x{:02X}", key).unwrap();
+                writeln!(code, "_XORED = bytes([{}])", bytes).unwrap();
+                writeln!(
+                    code,
+                    "TRACKING_STRING = bytes(b ^ _XOR_KEY for b in _XORED).decode(\"utf-8\")"
+                )
+                .unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "# Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "# string literal exists in the module's bytecode.").unwrap();
+                writeln!(code, "_chars = [None] * {}", string.chars().count()).unwrap();
+                for (i, ch) in string.chars().enumerate() {
+                    writeln!(
+                        code,
+                        "_chars[{}] = \"{}\"",
+                        i,
+                        EscapeProfile::Python.escape(&ch.to_string())
+                    )
+                    .unwrap();
+                }
+                writeln!(code, "TRACKING_STRING = \"\".join(_chars)").unwrap();
+
+                code
+            }
+        }
+    }
 }
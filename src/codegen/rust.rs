@@ -1,4 +1,4 @@
-use super::CodeGenerator;
+use super::{encoded_byte_list, xor_encode, CodeGenerator, EscapeProfile, ObfuscationMode};
 use std::fmt::Write;
 
 pub struct RustCodeGenerator;
@@ -19,7 +19,7 @@ impl CodeGenerator for RustCodeGenerator {
         writeln!(
             code,
             "static TRACKING_STRING: &[u8] = b\"{}\";",
-            escape_rust_bytes(string)
+            EscapeProfile::Rust.escape(string)
         )
         .unwrap();
         writeln!(code).unwrap();
@@ -35,18 +35,154 @@ impl CodeGenerator for RustCodeGenerator {
 
         code
     }
-}
 
-fn escape_rust_bytes(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            '"' => "\\\"".to_string(),
-            '\\' => "\\\\".to_string(),
-            '\n' => "\\n".to_string(),
-            '\r' => "\\r".to_string(),
-            '\t' => "\\t".to_string(),
-            _ if c.is_ascii_graphic() || c == ' ' => c.to_string(),
-            _ => format!("\\x{:02X}", c as u8),
-        })
-        .collect()
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, encoded_len, decoded_len) = encoded_byte_list(string);
+        let mut code = String::new();
+
+        writeln!(code, "// Tracking string - DO NOT REMOVE").unwrap();
+        writeln!(
+            code,
+            "// Yaz0-encoded and decoded once at startup to defeat naive `strings` scans"
+        )
+        .unwrap();
+        writeln!(code).unwrap();
+        writeln!(
+            code,
+            "static ENCODED_DATA: [u8; {}] = [{}];",
+            encoded_len, bytes
+        )
+        .unwrap();
+        writeln!(code, "const DECODED_LEN: usize = {};", decoded_len).unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "fn decode_tracking_string() -> Vec<u8> {{").unwrap();
+        writeln!(code, "    let mut out = Vec::with_capacity(DECODED_LEN);").unwrap();
+        writeln!(code, "    let mut i = 0;").unwrap();
+        writeln!(
+            code,
+            "    while out.len() < DECODED_LEN && i < ENCODED_DATA.len() {{"
+        )
+        .unwrap();
+        writeln!(code, "        let control = ENCODED_DATA[i];").unwrap();
+        writeln!(code, "        i += 1;").unwrap();
+        writeln!(
+            code,
+            "        for bit in (0..8).rev() {{"
+        )
+        .unwrap();
+        writeln!(
+            code,
+            "            if out.len() >= DECODED_LEN || i >= ENCODED_DATA.len() {{ break; }}"
+        )
+        .unwrap();
+        writeln!(code, "            if (control >> bit) & 1 == 1 {{").unwrap();
+        writeln!(code, "                out.push(ENCODED_DATA[i]);").unwrap();
+        writeln!(code, "                i += 1;").unwrap();
+        writeln!(code, "            }} else {{").unwrap();
+        writeln!(
+            code,
+            "                let (b0, b1) = (ENCODED_DATA[i], ENCODED_DATA[i + 1]);"
+        )
+        .unwrap();
+        writeln!(code, "                i += 2;").unwrap();
+        writeln!(
+            code,
+            "                let distance = (((b0 & 0x0F) as usize) << 8 | b1 as usize) + 1;"
+        )
+        .unwrap();
+        writeln!(code, "                let length = if (b0 >> 4) == 0 {{").unwrap();
+        writeln!(code, "                    let l = ENCODED_DATA[i] as usize + 0x12;").unwrap();
+        writeln!(code, "                    i += 1;").unwrap();
+        writeln!(code, "                    l").unwrap();
+        writeln!(code, "                }} else {{").unwrap();
+        writeln!(code, "                    (b0 >> 4) as usize + 2").unwrap();
+        writeln!(code, "                }};").unwrap();
+        writeln!(code, "                let start = out.len() - distance;").unwrap();
+        writeln!(code, "                for k in 0..length {{").unwrap();
+        writeln!(code, "                    out.push(out[start + k]);").unwrap();
+        writeln!(code, "                }}").unwrap();
+        writeln!(code, "            }}").unwrap();
+        writeln!(code, "        }}").unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "    out").unwrap();
+        writeln!(code, "}}").unwrap();
+        writeln!(code).unwrap();
+        writeln!(code, "#[inline(never)]").unwrap();
+        writeln!(code, "fn _tracking_init() {{").unwrap();
+        writeln!(code, "    let decoded = decode_tracking_string();").unwrap();
+        writeln!(
+            code,
+            "    let _ = unsafe {{ std::ptr::read_volatile(&decoded.as_slice()) }};"
+        )
+        .unwrap();
+        writeln!(code, "}}").unwrap();
+
+        code
+    }
+
+    fn generate_obfuscated(&self, string: &str, mode: ObfuscationMode) -> String {
+        match mode {
+            ObfuscationMode::None => self.generate(string),
+            ObfuscationMode::Xor => {
+                let (key, xored) = xor_encode(string);
+                let bytes = xored
+                    .iter()
+                    .map(|b| format!("0x{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is XOR-obfuscated so it doesn't sit in the binary as one literal"
+                )
+                .unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "const XOR_KEY: u8 = 0x{:02X};", key).unwrap();
+                writeln!(code, "static XORED: [u8; {}] = [{}];", xored.len(), bytes).unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "#[inline(never)]").unwrap();
+                writeln!(code, "fn _tracking_init() {{").unwrap();
+                writeln!(
+                    code,
+                    "    let decoded: Vec<u8> = XORED.iter().map(|b| b ^ XOR_KEY).collect();"
+                )
+                .unwrap();
+                writeln!(
+                    code,
+                    "    let _ = unsafe {{ std::ptr::read_volatile(&decoded.as_slice()) }};"
+                )
+                .unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+            ObfuscationMode::Stack => {
+                let mut code = String::new();
+
+                writeln!(
+                    code,
+                    "// Tracking string is built one character at a time, so no constant"
+                )
+                .unwrap();
+                writeln!(code, "// string literal exists in the binary.").unwrap();
+                writeln!(code).unwrap();
+                writeln!(code, "#[inline(never)]").unwrap();
+                writeln!(code, "fn _tracking_init() {{").unwrap();
+                writeln!(code, "    let mut buf = [0u8; {}];", string.len()).unwrap();
+                for (i, b) in string.bytes().enumerate() {
+                    writeln!(code, "    buf[{}] = 0x{:02X};", i, b).unwrap();
+                }
+                writeln!(
+                    code,
+                    "    let _ = unsafe {{ std::ptr::read_volatile(&buf.as_slice()) }};"
+                )
+                .unwrap();
+                writeln!(code, "}}").unwrap();
+
+                code
+            }
+        }
+    }
 }
+
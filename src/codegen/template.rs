@@ -0,0 +1,365 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Shared string-escaping rules, factored out of the per-language
+/// generators so `TemplateCodeGenerator` can pick one for `{{escaped}}`
+/// without duplicating escaping logic per target language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EscapeProfile {
+    /// C/C++ double-quoted string literals: non-printable bytes become
+    /// `\xHH` escapes rather than being emitted raw.
+    C,
+    Csharp,
+    Go,
+    Java,
+    JavaScript,
+    /// PowerShell double-quoted ("expandable") strings, which use a
+    /// backtick escape character instead of a backslash.
+    PowerShell,
+    Python,
+    /// Rust byte-string literals: non-printable bytes become `\xHH`
+    /// escapes rather than being emitted raw.
+    Rust,
+    /// No escaping at all, for templates targeting formats with no quoting
+    /// convention of their own (linker scripts, raw resource data, ...).
+    None,
+}
+
+impl EscapeProfile {
+    pub fn escape(&self, s: &str) -> String {
+        match self {
+            EscapeProfile::C => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    '\0' => "\\0".to_string(),
+                    _ if c.is_ascii_graphic() || c == ' ' => c.to_string(),
+                    _ => c.to_string().bytes().map(|b| format!("\\x{:02X}", b)).collect(),
+                })
+                .collect(),
+            EscapeProfile::Csharp => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    '\0' => "\\0".to_string(),
+                    _ => c.to_string(),
+                })
+                .collect(),
+            EscapeProfile::Go => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    _ => c.to_string(),
+                })
+                .collect(),
+            EscapeProfile::Java => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    '\0' => "\\0".to_string(),
+                    _ => c.to_string(),
+                })
+                .collect(),
+            EscapeProfile::JavaScript => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    '\0' => "\\0".to_string(),
+                    _ => c.to_string(),
+                })
+                .collect(),
+            EscapeProfile::PowerShell => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "`\"".to_string(),
+                    '`' => "``".to_string(),
+                    '$' => "`$".to_string(),
+                    '\n' => "`n".to_string(),
+                    '\r' => "`r".to_string(),
+                    '\t' => "`t".to_string(),
+                    '\0' => "`0".to_string(),
+                    _ => c.to_string(),
+                })
+                .collect(),
+            EscapeProfile::Python => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    _ => c.to_string(),
+                })
+                .collect(),
+            EscapeProfile::Rust => s
+                .chars()
+                .map(|c| match c {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    '\r' => "\\r".to_string(),
+                    '\t' => "\\t".to_string(),
+                    _ if c.is_ascii_graphic() || c == ' ' => c.to_string(),
+                    _ => c.to_string().bytes().map(|b| format!("\\x{:02X}", b)).collect(),
+                })
+                .collect(),
+            EscapeProfile::None => s.to_string(),
+        }
+    }
+}
+
+/// Renders a user-supplied template against a fixed variable set, so
+/// red-teamers can target a language or file format this crate has no
+/// built-in generator for (Swift, Kotlin, VBA, linker scripts, `.rc`
+/// resources, ...) without patching the crate.
+///
+/// Supported placeholders: `{{string}}` (raw), `{{escaped}}` (escaped per
+/// `profile`), `{{len}}`, `{{uuid}}` (a fresh v4 UUID), `{{tag}}` (the
+/// literal `REDBREADCRUMB`, a stable marker a template can key off of), and,
+/// in [`CodeGenerator::generate_encoded`], `{{bytes}}` (the Yaz0-compressed
+/// payload as a `0x..`-separated byte list) and `{{compressed_len}}` /
+/// `{{original_len}}`, so a template can write its own decode loop instead
+/// of relying on a built-in decoder stub.
+pub struct TemplateCodeGenerator {
+    template: String,
+    profile: EscapeProfile,
+}
+
+impl TemplateCodeGenerator {
+    pub fn new(template: String, profile: EscapeProfile) -> Self {
+        Self { template, profile }
+    }
+
+    pub fn render(&self, string: &str) -> String {
+        self.template
+            .replace("{{escaped}}", &self.profile.escape(string))
+            .replace("{{string}}", string)
+            .replace("{{len}}", &string.len().to_string())
+            .replace("{{uuid}}", &Uuid::new_v4().to_string())
+            .replace("{{tag}}", "REDBREADCRUMB")
+    }
+}
+
+impl super::CodeGenerator for TemplateCodeGenerator {
+    fn generate(&self, string: &str) -> String {
+        self.render(string)
+    }
+
+    fn generate_encoded(&self, string: &str) -> String {
+        let (bytes, compressed_len, original_len) = super::encoded_byte_list(string);
+        self.render(string)
+            .replace("{{bytes}}", &bytes)
+            .replace("{{compressed_len}}", &compressed_len.to_string())
+            .replace("{{original_len}}", &original_len.to_string())
+    }
+}
+
+/// One named entry in a `--generators` config file: a team's in-house
+/// library of [`TemplateCodeGenerator`] templates, so a generator can be
+/// selected by name with `--generator` instead of pointing `--template` at
+/// a one-off file each time.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeneratorEntry {
+    pub name: String,
+    #[serde(default = "default_profile")]
+    pub profile: EscapeProfile,
+    pub template: String,
+}
+
+fn default_profile() -> EscapeProfile {
+    EscapeProfile::None
+}
+
+/// Parse a `--generators` config file's contents: a JSON array of
+/// [`GeneratorEntry`] values.
+pub fn load_generators(src: &str) -> Result<Vec<GeneratorEntry>, serde_json::Error> {
+    serde_json::from_str(src)
+}
+
+/// Pick the entry a `--generator` name selects out of a loaded config,
+/// defaulting to the config's only entry when it has just one and no name
+/// was given.
+pub fn select_generator<'a>(
+    entries: &'a [GeneratorEntry],
+    name: Option<&str>,
+) -> Result<&'a GeneratorEntry, String> {
+    match name {
+        Some(name) => entries
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| format!("no generator named '{}' in this config", name)),
+        None => match entries {
+            [] => Err("generators config has no entries".to_string()),
+            [single] => Ok(single),
+            _ => {
+                let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+                Err(format!(
+                    "config has multiple generators ({}); pick one with --generator <NAME>",
+                    names.join(", ")
+                ))
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_profiles_leave_plain_text_untouched() {
+        for profile in [
+            EscapeProfile::C,
+            EscapeProfile::Csharp,
+            EscapeProfile::Go,
+            EscapeProfile::Java,
+            EscapeProfile::JavaScript,
+            EscapeProfile::PowerShell,
+            EscapeProfile::Python,
+            EscapeProfile::Rust,
+            EscapeProfile::None,
+        ] {
+            assert_eq!(profile.escape("TEST123"), "TEST123");
+        }
+    }
+
+    #[test]
+    fn test_c_profile_escapes_quotes_and_non_ascii() {
+        assert_eq!(EscapeProfile::C.escape("a\"b"), "a\\\"b");
+        assert_eq!(EscapeProfile::C.escape("\x01"), "\\x01");
+    }
+
+    #[test]
+    fn test_powershell_profile_uses_backtick() {
+        assert_eq!(EscapeProfile::PowerShell.escape("$x`\""), "`$x```\"");
+    }
+
+    #[test]
+    fn test_none_profile_is_a_no_op() {
+        assert_eq!(EscapeProfile::None.escape("a\"b\\c"), "a\"b\\c");
+    }
+
+    #[test]
+    fn test_render_substitutes_string_and_escaped() {
+        let gen = TemplateCodeGenerator::new(
+            "const char* S = \"{{escaped}}\"; // len={{len}}".to_string(),
+            EscapeProfile::C,
+        );
+        let out = gen.render("a\"b");
+        assert_eq!(out, "const char* S = \"a\\\"b\"; // len=3");
+    }
+
+    #[test]
+    fn test_render_substitutes_raw_string_tag_and_uuid() {
+        let gen = TemplateCodeGenerator::new(
+            "{{string}} {{tag}} {{uuid}}".to_string(),
+            EscapeProfile::None,
+        );
+        let out = gen.render("TEST");
+        assert!(out.starts_with("TEST REDBREADCRUMB "));
+        let uuid_part = out.rsplit(' ').next().unwrap();
+        assert!(Uuid::parse_str(uuid_part).is_ok());
+    }
+
+    #[test]
+    fn test_code_generator_trait_generate_matches_render() {
+        use super::super::CodeGenerator;
+        let gen = TemplateCodeGenerator::new("let s = \"{{escaped}}\";".to_string(), EscapeProfile::None);
+        assert_eq!(gen.generate("a\"b"), gen.render("a\"b"));
+    }
+
+    #[test]
+    fn test_code_generator_trait_generate_encoded_substitutes_bytes() {
+        use super::super::CodeGenerator;
+        let gen = TemplateCodeGenerator::new(
+            "bytes = [{{bytes}}]; // {{compressed_len}}/{{original_len}}".to_string(),
+            EscapeProfile::None,
+        );
+        let out = gen.generate_encoded("TEST123");
+        assert!(out.contains("0x"));
+        assert!(!out.contains("{{bytes}}"));
+        assert!(!out.contains("{{compressed_len}}"));
+        assert!(!out.contains("{{original_len}}"));
+    }
+
+    #[test]
+    fn test_load_generators_parses_config() {
+        let json = r#"[
+            {"name": "nim", "profile": "none", "template": "let s = \"{{string}}\""},
+            {"name": "zig", "profile": "c", "template": "const s = \"{{escaped}}\";"}
+        ]"#;
+        let entries = load_generators(json).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "nim");
+        assert_eq!(entries[1].profile, EscapeProfile::C);
+    }
+
+    #[test]
+    fn test_load_generators_defaults_profile_to_none() {
+        let json = r#"[{"name": "raw", "template": "{{string}}"}]"#;
+        let entries = load_generators(json).unwrap();
+        assert_eq!(entries[0].profile, EscapeProfile::None);
+    }
+
+    #[test]
+    fn test_select_generator_by_name() {
+        let entries = vec![
+            GeneratorEntry { name: "nim".to_string(), profile: EscapeProfile::None, template: "a".to_string() },
+            GeneratorEntry { name: "zig".to_string(), profile: EscapeProfile::None, template: "b".to_string() },
+        ];
+        let picked = select_generator(&entries, Some("zig")).unwrap();
+        assert_eq!(picked.name, "zig");
+    }
+
+    #[test]
+    fn test_select_generator_defaults_to_only_entry() {
+        let entries = vec![GeneratorEntry {
+            name: "nim".to_string(),
+            profile: EscapeProfile::None,
+            template: "a".to_string(),
+        }];
+        let picked = select_generator(&entries, None).unwrap();
+        assert_eq!(picked.name, "nim");
+    }
+
+    #[test]
+    fn test_select_generator_requires_name_when_ambiguous() {
+        let entries = vec![
+            GeneratorEntry { name: "nim".to_string(), profile: EscapeProfile::None, template: "a".to_string() },
+            GeneratorEntry { name: "zig".to_string(), profile: EscapeProfile::None, template: "b".to_string() },
+        ];
+        assert!(select_generator(&entries, None).is_err());
+    }
+
+    #[test]
+    fn test_select_generator_unknown_name_errors() {
+        let entries = vec![GeneratorEntry {
+            name: "nim".to_string(),
+            profile: EscapeProfile::None,
+            template: "a".to_string(),
+        }];
+        assert!(select_generator(&entries, Some("missing")).is_err());
+    }
+}
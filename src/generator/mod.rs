@@ -1,41 +1,118 @@
 use rand::Rng;
 
+pub mod hkdf;
+
 const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const HEX_CHARS: &[u8] = b"0123456789ABCDEF";
+
+// Lagged Fibonacci generator parameters (j, k) = (24, 55), the same lag pair
+// nod-rs uses to fill disc junk data.
+const LAG_J: usize = 24;
+const LAG_K: usize = 55;
+
+/// A seeded, fully reproducible lagged Fibonacci generator.
+///
+/// Same seed + same lags always reproduce the same output stream, so a
+/// campaign can regenerate the exact identifiers it planted into binaries.
+struct LaggedFibonacci {
+    buf: [u32; LAG_K],
+    p: usize,
+}
+
+impl LaggedFibonacci {
+    fn new(seed: u64) -> Self {
+        let mut s = seed;
+        let mut buf = [0u32; LAG_K];
+        for word in buf.iter_mut() {
+            s = s
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            *word = (s >> 32) as u32;
+        }
+        Self { buf, p: LAG_K }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let j_idx = (self.p - LAG_J) % LAG_K;
+        let k_idx = (self.p - LAG_K) % LAG_K;
+        let w = self.buf[j_idx].wrapping_add(self.buf[k_idx]);
+        self.buf[self.p % LAG_K] = w;
+        self.p += 1;
+        w
+    }
+}
 
 pub struct StringGenerator {
     prefix: String,
+    seed: Option<u64>,
 }
 
 impl StringGenerator {
     pub fn new(prefix: String) -> Self {
-        Self { prefix }
+        Self { prefix, seed: None }
+    }
+
+    /// Create a generator in deterministic mode, backed by a lagged Fibonacci
+    /// generator seeded from `seed`. Every string produced from the same seed
+    /// is reproducible on any machine, so a campaign can rebuild the exact
+    /// identifiers it planted without having stored them.
+    pub fn with_seed(prefix: String, seed: u64) -> Self {
+        Self {
+            prefix,
+            seed: Some(seed),
+        }
     }
 
     pub fn generate(&self, length: usize) -> String {
-        let mut rng = rand::thread_rng();
         let suffix_len = length.saturating_sub(self.prefix.len());
 
-        let suffix: String = (0..suffix_len)
-            .map(|_| {
-                let idx = rng.gen_range(0..ALPHANUMERIC.len());
-                ALPHANUMERIC[idx] as char
-            })
-            .collect();
+        let suffix: String = match self.seed {
+            Some(seed) => {
+                let mut lfg = LaggedFibonacci::new(seed);
+                (0..suffix_len)
+                    .map(|_| {
+                        let w = lfg.next_u32();
+                        ALPHANUMERIC[w as usize % ALPHANUMERIC.len()] as char
+                    })
+                    .collect()
+            }
+            None => {
+                let mut rng = rand::thread_rng();
+                (0..suffix_len)
+                    .map(|_| {
+                        let idx = rng.gen_range(0..ALPHANUMERIC.len());
+                        ALPHANUMERIC[idx] as char
+                    })
+                    .collect()
+            }
+        };
 
         format!("{}{}", self.prefix, suffix)
     }
 
     pub fn generate_hex(&self, length: usize) -> String {
-        let mut rng = rand::thread_rng();
-        let hex_chars = b"0123456789ABCDEF";
         let suffix_len = length.saturating_sub(self.prefix.len());
 
-        let suffix: String = (0..suffix_len)
-            .map(|_| {
-                let idx = rng.gen_range(0..hex_chars.len());
-                hex_chars[idx] as char
-            })
-            .collect();
+        let suffix: String = match self.seed {
+            Some(seed) => {
+                let mut lfg = LaggedFibonacci::new(seed);
+                (0..suffix_len)
+                    .map(|_| {
+                        let w = lfg.next_u32();
+                        HEX_CHARS[w as usize % HEX_CHARS.len()] as char
+                    })
+                    .collect()
+            }
+            None => {
+                let mut rng = rand::thread_rng();
+                (0..suffix_len)
+                    .map(|_| {
+                        let idx = rng.gen_range(0..HEX_CHARS.len());
+                        HEX_CHARS[idx] as char
+                    })
+                    .collect()
+            }
+        };
 
         format!("{}{}", self.prefix, suffix)
     }
@@ -47,6 +124,59 @@ impl Default for StringGenerator {
     }
 }
 
+/// Fixed HKDF salt for this tool's keyed derivation mode. It doesn't need to
+/// be secret - only the master key does - but pinning it keeps derivations
+/// from colliding with any other HKDF usage of the same master key.
+const HKDF_SALT: &[u8] = b"bREDcrumb-hkdf-v1";
+
+/// Derive a tracking string from `master_key` and `recipient_label` via
+/// HKDF (RFC 5869) over HMAC-SHA256, Base32-encoding the output so it's a
+/// plain printable-ASCII literal. The same key + label always reproduces
+/// the same string, so it never needs to be stored in the clear to later
+/// attribute a leaked copy back to its recipient.
+pub fn derive_tracking_string(prefix: &str, master_key: &[u8], recipient_label: &str, length: usize) -> String {
+    let suffix_len = length.saturating_sub(prefix.len());
+    // Base32 packs 5 bits per character, so round the byte count up to cover
+    // at least `suffix_len` characters.
+    let needed_bytes = (suffix_len * 5).div_ceil(8).max(1);
+
+    let prk = hkdf::hkdf_extract(HKDF_SALT, master_key);
+    let okm = hkdf::hkdf_expand(&prk, recipient_label.as_bytes(), needed_bytes);
+    let encoded = hkdf::base32_encode(&okm);
+
+    let suffix: String = encoded.chars().take(suffix_len).collect();
+    format!("{}{}", prefix, suffix)
+}
+
+/// A short, non-secret fingerprint of a master key (first 8 bytes of its
+/// SHA-256 digest, hex-encoded) for recording which key produced a batch of
+/// derived strings without storing the key itself.
+pub fn key_fingerprint(master_key: &[u8]) -> String {
+    hkdf::sha256(master_key)[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Given a leaked string fragment, recompute the derived tracking string for
+/// every candidate recipient label and return the one it matches, using a
+/// constant-time comparison. Returns `None` if no label reproduces it.
+pub fn attribute(
+    fragment: &str,
+    prefix: &str,
+    master_key: &[u8],
+    labels: &[String],
+) -> Option<String> {
+    let length = fragment.len();
+    labels
+        .iter()
+        .find(|label| {
+            let candidate = derive_tracking_string(prefix, master_key, label, length);
+            hkdf::constant_time_eq(candidate.as_bytes(), fragment.as_bytes())
+        })
+        .cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +195,72 @@ mod tests {
         let s = gen.generate(100);
         assert!(s.chars().all(|c| c.is_alphanumeric()));
     }
+
+    #[test]
+    fn test_seeded_is_deterministic() {
+        let a = StringGenerator::with_seed("RT".to_string(), 42).generate(32);
+        let b = StringGenerator::with_seed("RT".to_string(), 42).generate(32);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_seeded_differs_by_seed() {
+        let a = StringGenerator::with_seed("RT".to_string(), 1).generate(32);
+        let b = StringGenerator::with_seed("RT".to_string(), 2).generate(32);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_seeded_hex_is_deterministic() {
+        let a = StringGenerator::with_seed("RT".to_string(), 7).generate_hex(24);
+        let b = StringGenerator::with_seed("RT".to_string(), 7).generate_hex(24);
+        assert_eq!(a, b);
+        assert!(a[2..].chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_derive_tracking_string_is_deterministic() {
+        let a = derive_tracking_string("RT", b"master-secret", "alice@example.com", 24);
+        let b = derive_tracking_string("RT", b"master-secret", "alice@example.com", 24);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 24);
+        assert!(a.starts_with("RT"));
+    }
+
+    #[test]
+    fn test_derive_tracking_string_differs_by_label() {
+        let a = derive_tracking_string("RT", b"master-secret", "alice@example.com", 24);
+        let b = derive_tracking_string("RT", b"master-secret", "bob@example.com", 24);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_derive_tracking_string_differs_by_key() {
+        let a = derive_tracking_string("RT", b"key-one", "alice@example.com", 24);
+        let b = derive_tracking_string("RT", b"key-two", "alice@example.com", 24);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_key_fingerprint_is_deterministic_and_short() {
+        let a = key_fingerprint(b"master-secret");
+        let b = key_fingerprint(b"master-secret");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 16);
+    }
+
+    #[test]
+    fn test_attribute_finds_matching_label() {
+        let labels = vec!["alice@example.com".to_string(), "bob@example.com".to_string()];
+        let leaked = derive_tracking_string("RT", b"master-secret", "bob@example.com", 24);
+        let found = attribute(&leaked, "RT", b"master-secret", &labels);
+        assert_eq!(found, Some("bob@example.com".to_string()));
+    }
+
+    #[test]
+    fn test_attribute_returns_none_for_unknown_fragment() {
+        let labels = vec!["alice@example.com".to_string()];
+        let found = attribute("RTnotarealfragment", "RT", b"master-secret", &labels);
+        assert_eq!(found, None);
+    }
 }
@@ -1,6 +1,7 @@
 pub mod codegen;
 pub mod generator;
 pub mod patcher;
+pub mod scanner;
 pub mod storage;
 pub mod yara;
 
@@ -11,15 +12,17 @@ pub mod cli;
 pub mod wasm;
 
 #[cfg(not(target_arch = "wasm32"))]
-pub use cli::{Cli, Commands, Language, PatchStrategy};
+pub use cli::{Cli, Commands, Encoding, Language, PatchStrategy};
 
 pub use codegen::{
-    CCodeGenerator, CSharpCodeGenerator, CodeGenerator, GoCodeGenerator,
-    JavaCodeGenerator, JavaScriptCodeGenerator, PowerShellCodeGenerator, PythonCodeGenerator,
-    RustCodeGenerator,
+    load_generators, select_generator, CCodeGenerator, CSharpCodeGenerator, CodeGenerator,
+    EscapeProfile, GeneratorEntry, GoCodeGenerator, JavaCodeGenerator, JavaScriptCodeGenerator,
+    ObfuscationMode, ObjectCodeGenerator, PowerShellCodeGenerator, PythonCodeGenerator,
+    RustCodeGenerator, TemplateCodeGenerator,
 };
 pub use generator::StringGenerator;
 pub use patcher::BinaryPatcher;
+pub use scanner::{Severity, StringScanner};
 pub use storage::{BinaryFormat, TrackedString};
 pub use yara::{YaraGenerator, YaraOptions};
 
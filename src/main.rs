@@ -1,18 +1,22 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use redbreadcrumb::{
-    cli::{Cli, Commands, Language},
+    cli::{Cli, Commands, Language, PatchStrategy},
     codegen::{
-        CCodeGenerator, CSharpCodeGenerator, CodeGenerator, GoCodeGenerator,
-        JavaCodeGenerator, JavaScriptCodeGenerator, PowerShellCodeGenerator, PythonCodeGenerator,
-        RustCodeGenerator,
+        load_generators, select_generator, CCodeGenerator, CSharpCodeGenerator, CodeGenerator,
+        EscapeProfile, GoCodeGenerator, JavaCodeGenerator, JavaScriptCodeGenerator,
+        ObfuscationMode, ObjectCodeGenerator, PowerShellCodeGenerator, PythonCodeGenerator,
+        RustCodeGenerator, TemplateCodeGenerator,
     },
-    generator::StringGenerator,
+    generator::{attribute, derive_tracking_string, key_fingerprint, StringGenerator},
     patcher::BinaryPatcher,
+    scanner::{ScanHit, StringScanner},
     storage::{Storage, TrackedString},
     yara::{YaraGenerator, YaraOptions},
 };
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() {
     if let Err(e) = run() {
@@ -25,24 +29,59 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Generate { length, tag, prefix, custom } => {
-            cmd_generate(length, tag, prefix, custom, cli.verbose)?;
+        Commands::Generate {
+            length,
+            tag,
+            prefix,
+            custom,
+            seed,
+            master_key,
+            label,
+        } => {
+            cmd_generate(length, tag, prefix, custom, seed, master_key, label, cli.verbose)?;
         }
         Commands::Yara {
             string,
             ascii,
             wide,
+            nocase,
+            fullword,
             name,
+            from_db,
+            tag,
+            all,
+            per_tag,
             output,
         } => {
-            cmd_yara(&string, ascii, wide, name.as_deref(), output, cli.verbose)?;
+            cmd_yara(
+                string.as_deref(),
+                ascii,
+                wide,
+                nocase,
+                fullword,
+                name.as_deref(),
+                from_db,
+                tag.as_deref(),
+                all,
+                per_tag,
+                output,
+                cli.verbose,
+            )?;
         }
         Commands::Code {
             string,
             language,
+            encoded,
+            encode,
+            template,
+            generators,
+            generator,
             output,
         } => {
-            cmd_code(&string, language, output, cli.verbose)?;
+            cmd_code(
+                &string, language, encoded, encode, template, generators, generator, output,
+                cli.verbose,
+            )?;
         }
         Commands::Patch {
             binary,
@@ -50,8 +89,13 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             output,
             strategy,
             force,
+            resilient,
+            rs_n,
+            rs_k,
         } => {
-            cmd_patch(binary, &string, output, strategy, force, cli.verbose)?;
+            cmd_patch(
+                binary, &string, output, strategy, force, resilient, rs_n, rs_k, cli.verbose,
+            )?;
         }
         Commands::List { tag, json } => {
             cmd_list(tag.as_deref(), json)?;
@@ -59,6 +103,32 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Show { identifier } => {
             cmd_show(&identifier)?;
         }
+        Commands::Export { output, tag } => {
+            cmd_export(tag.as_deref(), output, cli.verbose)?;
+        }
+        Commands::Import { input } => {
+            cmd_import(&input, cli.verbose)?;
+        }
+        Commands::Attribute {
+            fragment,
+            prefix,
+            master_key,
+            labels,
+        } => {
+            cmd_attribute(&fragment, &prefix, &master_key, &labels)?;
+        }
+        Commands::Scan { path, tag, json, wide } => {
+            cmd_scan(&path, tag.as_deref(), json, wide, cli.verbose)?;
+        }
+        Commands::Batch { manifest, report } => {
+            cmd_batch(&manifest, report, cli.verbose)?;
+        }
+        Commands::Completions { shell, man } => {
+            cmd_completions(shell, man)?;
+        }
+        Commands::Recover { binary } => {
+            cmd_recover(&binary, cli.verbose)?;
+        }
     }
 
     Ok(())
@@ -69,26 +139,46 @@ fn cmd_generate(
     tag: Option<String>,
     prefix: String,
     custom: Option<String>,
+    seed: Option<u64>,
+    master_key: Option<String>,
+    label: Option<String>,
     verbose: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let value = if let Some(custom_str) = custom {
+    let (value, recipient_label, key_fp) = if let Some(custom_str) = custom {
         if verbose {
             eprintln!("Using custom string: {}", custom_str);
         }
-        custom_str
+        (custom_str, None, None)
+    } else if let (Some(master_key_hex), Some(label)) = (master_key, label) {
+        let master_key = decode_hex(&master_key_hex)?;
+        let generated = derive_tracking_string(&prefix, &master_key, &label, length);
+        if verbose {
+            eprintln!("Derived string via HKDF for recipient '{}'", label);
+        }
+        let fingerprint = key_fingerprint(&master_key);
+        (generated, Some(label), Some(fingerprint))
     } else {
-        let generator = StringGenerator::new(prefix);
+        let generator = match seed {
+            Some(seed) => StringGenerator::with_seed(prefix, seed),
+            None => StringGenerator::new(prefix),
+        };
         let generated = generator.generate(length);
         if verbose {
-            eprintln!("Generated string of length {}", length);
+            if let Some(seed) = seed {
+                eprintln!("Generated string of length {} from seed {}", length, seed);
+            } else {
+                eprintln!("Generated string of length {}", length);
+            }
         }
-        generated
+        (generated, None, None)
     };
 
     // Store in database
     let storage = Storage::new()?;
     let tags = tag.map(|t| vec![t]).unwrap_or_default();
-    let tracked = TrackedString::new(value.clone(), None, tags);
+    let mut tracked = TrackedString::new(value.clone(), None, tags);
+    tracked.recipient_label = recipient_label;
+    tracked.key_fingerprint = key_fp;
 
     if verbose {
         eprintln!("Storing with ID: {}", tracked.id);
@@ -101,26 +191,395 @@ fn cmd_generate(
     Ok(())
 }
 
+fn cmd_attribute(
+    fragment: &str,
+    prefix: &str,
+    master_key_hex: &str,
+    labels: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let master_key = decode_hex(master_key_hex)?;
+
+    match attribute(fragment, prefix, &master_key, labels) {
+        Some(label) => {
+            println!("Attributed to: {}", label);
+        }
+        None => {
+            println!("No candidate label reproduces this fragment.");
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if s.len() % 2 != 0 {
+        return Err("hex string must have an even number of digits".into());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| e.into()))
+        .collect()
+}
+
+fn cmd_scan(
+    path: &PathBuf,
+    tag: Option<&str>,
+    json: bool,
+    wide: bool,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Storage::new()?;
+    let mut tracked = storage.list_all()?;
+    if let Some(tag) = tag {
+        tracked.retain(|s| s.tags.iter().any(|t| t == tag));
+    }
+
+    if tracked.is_empty() {
+        println!("No tracked strings in the database to scan for.");
+        return Ok(());
+    }
+
+    let files = collect_files(path)?;
+    if verbose {
+        eprintln!(
+            "Scanning {} file(s) for {} tracked string(s) across a rayon thread pool{}",
+            files.len(),
+            tracked.len(),
+            if wide { " (ascii + wide)" } else { "" }
+        );
+    }
+
+    // One Aho-Corasick automaton over every tracked string is shared
+    // read-only across the pool; each file is read and matched against it
+    // on its own thread so a large tree and a large campaign DB both scan
+    // in a single parallel sweep instead of file-by-file.
+    let found: Vec<(PathBuf, ScanHit)> = files
+        .par_iter()
+        .map(|file| -> Result<Vec<(PathBuf, ScanHit)>, std::io::Error> {
+            let data = fs::read(file)?;
+            Ok(StringScanner::scan(&data, &tracked, wide)
+                .into_iter()
+                .map(|hit| (file.clone(), hit))
+                .collect())
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if json {
+        let output: Vec<_> = found
+            .iter()
+            .map(|(file, hit)| {
+                serde_json::json!({
+                    "file": file,
+                    "id": hit.id,
+                    "value": hit.value,
+                    "tags": hit.tags,
+                    "offset": hit.offset,
+                    "wide": hit.wide,
+                    "severity": hit.severity,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if found.is_empty() {
+        println!("No tracked strings found.");
+    } else {
+        for (file, hit) in &found {
+            println!(
+                "[{}] {}: {} (id={}, offset=0x{:X}{}, tags=[{}])",
+                hit.severity,
+                file.display(),
+                hit.value,
+                hit.id,
+                hit.offset,
+                if hit.wide { ", wide" } else { "" },
+                hit.tags.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Recursively collect every regular file under `path` (or just `path`
+/// itself if it's already a file).
+fn collect_files(path: &PathBuf) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    let mut stack = vec![path.clone()];
+
+    while let Some(current) = stack.pop() {
+        if current.is_dir() {
+            for entry in fs::read_dir(&current)? {
+                stack.push(entry?.path());
+            }
+        } else if current.is_file() {
+            files.push(current);
+        }
+    }
+
+    Ok(files)
+}
+
+/// One job in a `batch` manifest. `action` selects which of `generate`,
+/// `code`, `yara`, or `patch` this job runs; the rest of the fields feed
+/// whichever of those the action needs, mirroring the matching CLI
+/// subcommand's options so a manifest job reads like its one-shot
+/// equivalent.
+#[derive(Deserialize)]
+struct BatchJob {
+    action: String,
+    #[serde(default = "default_batch_length")]
+    length: usize,
+    #[serde(default = "default_batch_prefix")]
+    prefix: String,
+    tag: Option<String>,
+    /// Custom string instead of a random one (`generate`), or the tracking
+    /// string to embed (`code`/`yara`/`patch`).
+    custom: Option<String>,
+    language: Option<String>,
+    binary: Option<PathBuf>,
+    strategy: Option<String>,
+    output: Option<PathBuf>,
+}
+
+fn default_batch_length() -> usize {
+    12
+}
+
+fn default_batch_prefix() -> String {
+    "RT".to_string()
+}
+
+/// One job's outcome in a batch run's summary report.
+#[derive(Serialize)]
+struct BatchJobResult {
+    action: String,
+    status: &'static str,
+    value: Option<String>,
+    output: Option<String>,
+    virtual_address: Option<u64>,
+    file_offset: Option<u64>,
+    error: Option<String>,
+}
+
+fn cmd_batch(
+    manifest: &PathBuf,
+    report: Option<PathBuf>,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_src = fs::read_to_string(manifest)?;
+    let jobs: Vec<BatchJob> = serde_json::from_str(&manifest_src)?;
+
+    if verbose {
+        eprintln!("Running {} batch job(s) from {}", jobs.len(), manifest.display());
+    }
+
+    let storage = Storage::new()?;
+    let mut results = Vec::with_capacity(jobs.len());
+
+    for (index, job) in jobs.iter().enumerate() {
+        let result = match run_batch_job(job, &storage) {
+            Ok(result) => result,
+            Err(e) => BatchJobResult {
+                action: job.action.clone(),
+                status: "error",
+                value: None,
+                output: None,
+                virtual_address: None,
+                file_offset: None,
+                error: Some(e.to_string()),
+            },
+        };
+        if verbose {
+            eprintln!("[{}] {}: {}", index, job.action, result.status);
+        }
+        results.push(result);
+    }
+
+    let report_json = serde_json::to_string_pretty(&results)?;
+    match report {
+        Some(path) => fs::write(&path, &report_json)?,
+        None => println!("{}", report_json),
+    }
+
+    Ok(())
+}
+
+/// Run a single batch job, reusing the same generator/codegen/yara/patcher
+/// calls the matching one-shot subcommand makes.
+fn run_batch_job(
+    job: &BatchJob,
+    storage: &Storage,
+) -> Result<BatchJobResult, Box<dyn std::error::Error>> {
+    match job.action.as_str() {
+        "generate" => {
+            let value = job
+                .custom
+                .clone()
+                .unwrap_or_else(|| StringGenerator::new(job.prefix.clone()).generate(job.length));
+
+            let tags = job.tag.clone().map(|t| vec![t]).unwrap_or_default();
+            storage.add_string(TrackedString::new(value.clone(), None, tags))?;
+
+            Ok(BatchJobResult {
+                action: job.action.clone(),
+                status: "ok",
+                value: Some(value),
+                output: None,
+                virtual_address: None,
+                file_offset: None,
+                error: None,
+            })
+        }
+        "code" => {
+            let string = job
+                .custom
+                .as_deref()
+                .ok_or("a \"code\" job requires a \"custom\" string")?;
+            let language =
+                Language::from_str(job.language.as_deref().unwrap_or("c"), true)?;
+            let code = generate_code(string, language, false);
+
+            if let Some(path) = &job.output {
+                fs::write(path, &code)?;
+            }
+
+            Ok(BatchJobResult {
+                action: job.action.clone(),
+                status: "ok",
+                value: Some(string.to_string()),
+                output: job.output.as_ref().map(|p| p.display().to_string()),
+                virtual_address: None,
+                file_offset: None,
+                error: None,
+            })
+        }
+        "yara" => {
+            let string = job
+                .custom
+                .as_deref()
+                .ok_or("a \"yara\" job requires a \"custom\" string")?;
+            let options = YaraOptions {
+                ascii: true,
+                wide: false,
+                nocase: false,
+                fullword: false,
+            };
+            let rule = YaraGenerator::generate(string, None, &options);
+
+            if let Some(path) = &job.output {
+                fs::write(path, &rule)?;
+            }
+
+            Ok(BatchJobResult {
+                action: job.action.clone(),
+                status: "ok",
+                value: Some(string.to_string()),
+                output: job.output.as_ref().map(|p| p.display().to_string()),
+                virtual_address: None,
+                file_offset: None,
+                error: None,
+            })
+        }
+        "patch" => {
+            let string = job
+                .custom
+                .as_deref()
+                .ok_or("a \"patch\" job requires a \"custom\" string")?;
+            let binary = job
+                .binary
+                .clone()
+                .ok_or("a \"patch\" job requires a \"binary\" path")?;
+            let strategy =
+                PatchStrategy::from_str(job.strategy.as_deref().unwrap_or("cave"), true)?;
+            let output_path = job
+                .output
+                .clone()
+                .unwrap_or_else(|| default_patched_path(&binary));
+
+            let result = BinaryPatcher::patch(&binary, &output_path, string, strategy.into(), false)?;
+
+            if let Some(mut tracked) = storage.find_by_value(string)? {
+                let record =
+                    BinaryPatcher::create_patched_binary_record(&binary, &output_path, &result);
+                tracked.patched_binaries.push(record);
+                storage.update_string(tracked)?;
+            }
+
+            Ok(BatchJobResult {
+                action: job.action.clone(),
+                status: "ok",
+                value: Some(string.to_string()),
+                output: Some(output_path.display().to_string()),
+                virtual_address: result.virtual_address,
+                file_offset: result.file_offset,
+                error: None,
+            })
+        }
+        other => Err(format!("unknown batch action: {}", other).into()),
+    }
+}
+
 fn cmd_yara(
-    string: &str,
+    string: Option<&str>,
     ascii: bool,
     wide: bool,
+    nocase: bool,
+    fullword: bool,
     name: Option<&str>,
+    from_db: bool,
+    tag: Option<&str>,
+    all: bool,
+    per_tag: bool,
     output: Option<PathBuf>,
     verbose: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let options = YaraOptions {
         ascii,
         wide,
-        nocase: false,
-        fullword: false,
+        nocase,
+        fullword,
     };
 
-    let rule = YaraGenerator::generate(string, name, &options);
+    let rule = if from_db {
+        let storage = Storage::new()?;
+        let strings = match tag {
+            Some(tag) => storage.list_by_tag(tag)?,
+            None => storage.list_all()?,
+        };
 
-    if verbose {
-        eprintln!("Generated YARA rule for: {}", string);
-    }
+        if per_tag {
+            if verbose {
+                eprintln!(
+                    "Generating one YARA rule per tag from {} tracked string(s)",
+                    strings.len()
+                );
+            }
+
+            YaraGenerator::generate_per_tag(&strings, &options, all)
+        } else {
+            if verbose {
+                eprintln!(
+                    "Generating consolidated YARA rule from {} tracked string(s){}",
+                    strings.len(),
+                    tag.map(|t| format!(" tagged \"{}\"", t)).unwrap_or_default()
+                );
+            }
+
+            YaraGenerator::generate_from_tracked(&strings, name, &options, all)
+        }
+    } else {
+        let string = string.expect("clap requires `string` unless --from-db is set");
+
+        if verbose {
+            eprintln!("Generated YARA rule for: {}", string);
+        }
+
+        YaraGenerator::generate(string, name, &options)
+    };
 
     if let Some(path) = output {
         fs::write(&path, &rule)?;
@@ -137,38 +596,154 @@ fn cmd_yara(
 fn cmd_code(
     string: &str,
     language: Language,
+    encoded: bool,
+    encode: redbreadcrumb::cli::Encoding,
+    template: Option<PathBuf>,
+    generators: Option<PathBuf>,
+    generator: Option<String>,
     output: Option<PathBuf>,
     verbose: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let code = generate_code(string, language);
-
-    if verbose {
-        eprintln!("Generated {} code snippet", language);
-    }
+    let code = if let Some(template_path) = template {
+        let template_src = fs::read_to_string(&template_path)?;
+        let gen = TemplateCodeGenerator::new(template_src, escape_profile_for(language));
+        if verbose {
+            eprintln!(
+                "Rendering user template {} ({} escaping)",
+                template_path.display(),
+                language
+            );
+        }
+        gen.render(string).into_bytes()
+    } else if let Some(generators_path) = generators {
+        let config_src = fs::read_to_string(&generators_path)?;
+        let entries = load_generators(&config_src)?;
+        let entry = select_generator(&entries, generator.as_deref())?;
+        let gen = TemplateCodeGenerator::new(entry.template.clone(), entry.profile);
+        if verbose {
+            eprintln!(
+                "Rendering generator '{}' from {}{}",
+                entry.name,
+                generators_path.display(),
+                if encoded { " (Yaz0-encoded)" } else { "" }
+            );
+        }
+        if encoded {
+            gen.generate_encoded(string).into_bytes()
+        } else {
+            gen.generate(string).into_bytes()
+        }
+    } else if !matches!(encode, redbreadcrumb::cli::Encoding::None) {
+        if verbose {
+            eprintln!("Generated {} code snippet (--encode {})", language, encode);
+        }
+        generate_code_obfuscated(string, language, encode.into())
+    } else {
+        if verbose {
+            eprintln!(
+                "Generated {} code snippet{}",
+                language,
+                if encoded { " (Yaz0-encoded)" } else { "" }
+            );
+        }
+        generate_code(string, language, encoded)
+    };
 
     if let Some(path) = output {
         fs::write(&path, &code)?;
         if verbose {
             eprintln!("Written to: {}", path.display());
         }
+    } else if let Ok(text) = std::str::from_utf8(&code) {
+        println!("{}", text);
     } else {
-        println!("{}", code);
+        use std::io::Write;
+        std::io::stdout().write_all(&code)?;
     }
 
     Ok(())
 }
 
-fn generate_code(string: &str, language: Language) -> String {
+/// Picks the `{{escaped}}` escaping rules a `--template` should use for a
+/// given `--language`, mirroring the quoting convention of that language's
+/// built-in generator. Object-file targets have no string-literal syntax of
+/// their own, so templates for them get the string back unescaped.
+fn escape_profile_for(language: Language) -> EscapeProfile {
+    match language {
+        Language::C | Language::Cpp => EscapeProfile::C,
+        Language::Python => EscapeProfile::Python,
+        Language::Go => EscapeProfile::Go,
+        Language::Rust => EscapeProfile::Rust,
+        Language::Csharp => EscapeProfile::Csharp,
+        Language::Javascript => EscapeProfile::JavaScript,
+        Language::Powershell => EscapeProfile::PowerShell,
+        Language::Java => EscapeProfile::Java,
+        Language::ElfObject | Language::CoffObject => EscapeProfile::None,
+    }
+}
+
+fn generate_code(string: &str, language: Language, encoded: bool) -> Vec<u8> {
+    match language {
+        Language::C if encoded => CCodeGenerator::new(false).generate_encoded(string).into_bytes(),
+        Language::C => CCodeGenerator::new(false).generate(string).into_bytes(),
+        Language::Cpp if encoded => CCodeGenerator::new(true).generate_encoded(string).into_bytes(),
+        Language::Cpp => CCodeGenerator::new(true).generate(string).into_bytes(),
+        Language::Python if encoded => PythonCodeGenerator.generate_encoded(string).into_bytes(),
+        Language::Python => PythonCodeGenerator.generate(string).into_bytes(),
+        Language::Go if encoded => GoCodeGenerator.generate_encoded(string).into_bytes(),
+        Language::Go => GoCodeGenerator.generate(string).into_bytes(),
+        Language::Rust if encoded => RustCodeGenerator.generate_encoded(string).into_bytes(),
+        Language::Rust => RustCodeGenerator.generate(string).into_bytes(),
+        Language::Csharp if encoded => CSharpCodeGenerator.generate_encoded(string).into_bytes(),
+        Language::Csharp => CSharpCodeGenerator.generate(string).into_bytes(),
+        Language::Javascript if encoded => {
+            JavaScriptCodeGenerator.generate_encoded(string).into_bytes()
+        }
+        Language::Javascript => JavaScriptCodeGenerator.generate(string).into_bytes(),
+        Language::Powershell if encoded => {
+            PowerShellCodeGenerator.generate_encoded(string).into_bytes()
+        }
+        Language::Powershell => PowerShellCodeGenerator.generate(string).into_bytes(),
+        Language::Java if encoded => JavaCodeGenerator.generate_encoded(string).into_bytes(),
+        Language::Java => JavaCodeGenerator.generate(string).into_bytes(),
+        Language::ElfObject => ObjectCodeGenerator.generate_elf(string),
+        Language::CoffObject => ObjectCodeGenerator.generate_coff(string),
+    }
+}
+
+/// Same dispatch as [`generate_code`], but for `--encode`: reconstructs the
+/// string at runtime per `mode` instead of a plain/Yaz0 literal. Object-file
+/// targets have no generator-level obfuscation of their own, so they fall
+/// back to their plain output regardless of `mode`.
+fn generate_code_obfuscated(string: &str, language: Language, mode: ObfuscationMode) -> Vec<u8> {
     match language {
-        Language::C => CCodeGenerator::new(false).generate(string),
-        Language::Cpp => CCodeGenerator::new(true).generate(string),
-        Language::Python => PythonCodeGenerator.generate(string),
-        Language::Go => GoCodeGenerator.generate(string),
-        Language::Rust => RustCodeGenerator.generate(string),
-        Language::Csharp => CSharpCodeGenerator.generate(string),
-        Language::Javascript => JavaScriptCodeGenerator.generate(string),
-        Language::Powershell => PowerShellCodeGenerator.generate(string),
-        Language::Java => JavaCodeGenerator.generate(string),
+        Language::C => CCodeGenerator::new(false)
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Cpp => CCodeGenerator::new(true)
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Python => PythonCodeGenerator
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Go => GoCodeGenerator.generate_obfuscated(string, mode).into_bytes(),
+        Language::Rust => RustCodeGenerator
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Csharp => CSharpCodeGenerator
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Javascript => JavaScriptCodeGenerator
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Powershell => PowerShellCodeGenerator
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::Java => JavaCodeGenerator
+            .generate_obfuscated(string, mode)
+            .into_bytes(),
+        Language::ElfObject => ObjectCodeGenerator.generate_elf(string),
+        Language::CoffObject => ObjectCodeGenerator.generate_coff(string),
     }
 }
 
@@ -178,33 +753,36 @@ fn cmd_patch(
     output: Option<PathBuf>,
     strategy: redbreadcrumb::cli::PatchStrategy,
     force: bool,
+    resilient: bool,
+    rs_n: u8,
+    rs_k: u8,
     verbose: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let output_path = output.unwrap_or_else(|| {
-        let stem = binary.file_stem().unwrap_or_default().to_string_lossy();
-        let ext = binary.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
-        let new_name = if ext.is_empty() {
-            format!("{}_patched", stem)
-        } else {
-            format!("{}_patched.{}", stem, ext)
-        };
-        binary.with_file_name(new_name)
-    });
+    let output_path = output.unwrap_or_else(|| default_patched_path(&binary));
 
     if verbose {
         eprintln!("Patching: {}", binary.display());
         eprintln!("Output: {}", output_path.display());
         eprintln!("String: {}", string);
         eprintln!("Strategy: {}", strategy);
+        if resilient {
+            eprintln!("Resilient: RS({}, {})", rs_n, rs_k);
+        }
     }
 
-    let result = BinaryPatcher::patch(
-        &binary,
-        &output_path,
-        string,
-        strategy.into(),
-        force,
-    )?;
+    let result = if resilient {
+        BinaryPatcher::patch_resilient(
+            &binary,
+            &output_path,
+            string,
+            rs_n,
+            rs_k,
+            strategy.into(),
+            force,
+        )?
+    } else {
+        BinaryPatcher::patch(&binary, &output_path, string, strategy.into(), force)?
+    };
 
     println!("Successfully patched binary!");
     println!("  Format: {}", result.format);
@@ -215,6 +793,12 @@ fn cmd_patch(
     if let Some(offset) = result.file_offset {
         println!("  File Offset: 0x{:X}", offset);
     }
+    if let Some((n, k)) = result.rs_params {
+        println!("  Reed-Solomon: RS({}, {})", n, k);
+    }
+    if result.signature_stripped {
+        println!("  Signature: stripped (binary was Authenticode-signed)");
+    }
     println!("  Output: {}", output_path.display());
 
     // Update database
@@ -233,6 +817,34 @@ fn cmd_patch(
     Ok(())
 }
 
+/// Default `<stem>_patched.<ext>` output path for a binary being patched,
+/// used whenever `--output`/a job's `"output"` field is omitted.
+fn default_patched_path(binary: &Path) -> PathBuf {
+    let stem = binary.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = binary.extension().map(|e| e.to_string_lossy()).unwrap_or_default();
+    let new_name = if ext.is_empty() {
+        format!("{}_patched", stem)
+    } else {
+        format!("{}_patched.{}", stem, ext)
+    };
+    binary.with_file_name(new_name)
+}
+
+fn cmd_recover(binary: &PathBuf, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if verbose {
+        eprintln!("Recovering resilient frame from: {}", binary.display());
+    }
+
+    let recovered = BinaryPatcher::recover(binary)?;
+
+    println!("Recovered tracking string: {}", recovered.string);
+    println!("  Layout: RS({}, {})", recovered.n, recovered.k);
+    println!("  Erasures: {}", recovered.erasures);
+    println!("  Corrected errors: {}", recovered.corrected_errors);
+
+    Ok(())
+}
+
 fn cmd_list(tag: Option<&str>, json: bool) -> Result<(), Box<dyn std::error::Error>> {
     let storage = Storage::new()?;
     let strings = if let Some(tag) = tag {
@@ -279,6 +891,12 @@ fn cmd_show(identifier: &str) -> Result<(), Box<dyn std::error::Error>> {
             }
             println!("Tags:    {}", s.tags.join(", "));
             println!("Created: {}", s.created_at.format("%Y-%m-%d %H:%M:%S UTC"));
+            if let Some(label) = &s.recipient_label {
+                println!("Recipient label: {}", label);
+            }
+            if let Some(fingerprint) = &s.key_fingerprint {
+                println!("Key fingerprint: {}", fingerprint);
+            }
 
             if !s.patched_binaries.is_empty() {
                 println!("\nPatched Binaries:");
@@ -288,6 +906,12 @@ fn cmd_show(identifier: &str) -> Result<(), Box<dyn std::error::Error>> {
                     if let Some(va) = pb.virtual_address {
                         println!("    VA: 0x{:X}", va);
                     }
+                    if let (Some(n), Some(k)) = (pb.rs_n, pb.rs_k) {
+                        println!("    Reed-Solomon: RS({}, {})", n, k);
+                    }
+                    if pb.signature_stripped {
+                        println!("    Signature: stripped");
+                    }
                 }
             }
         }
@@ -298,3 +922,115 @@ fn cmd_show(identifier: &str) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Exports tracked strings as a compact, line-oriented raw-hex interchange
+/// format: one `TrackedString` per line as `id\ttags\thex(value)`, hex so
+/// arbitrary/obfuscated byte sequences round-trip without the mangling
+/// plain text would risk.
+fn cmd_export(
+    tag: Option<&str>,
+    output: Option<PathBuf>,
+    verbose: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::fmt::Write as _;
+
+    let storage = Storage::new()?;
+    let strings = match tag {
+        Some(tag) => storage.list_by_tag(tag)?,
+        None => storage.list_all()?,
+    };
+
+    if verbose {
+        eprintln!("Exporting {} tracked string(s)", strings.len());
+    }
+
+    let mut vectors = String::new();
+    for s in &strings {
+        let hex: String = s.value.bytes().map(|b| format!("{:02x}", b)).collect();
+        writeln!(vectors, "{}\t{}\t{}", s.id, s.tags.join(","), hex)?;
+    }
+
+    match output {
+        Some(path) => fs::write(&path, &vectors)?,
+        None => print!("{}", vectors),
+    }
+
+    Ok(())
+}
+
+/// Imports tracked strings from the line-oriented raw-hex format `export`
+/// produces, decoding each value's hex back into UTF-8 and skipping any
+/// value already present in the database.
+fn cmd_import(input: &PathBuf, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Storage::new()?;
+    let contents = fs::read_to_string(input)?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+
+    for (line_no, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut fields = line.splitn(3, '\t');
+        let id = fields
+            .next()
+            .ok_or_else(|| format!("line {}: missing id field", line_no + 1))?;
+        let tags = fields
+            .next()
+            .ok_or_else(|| format!("line {}: missing tags field", line_no + 1))?;
+        let hex = fields
+            .next()
+            .ok_or_else(|| format!("line {}: missing hex value field", line_no + 1))?;
+
+        let value = String::from_utf8(decode_hex(hex)?)?;
+
+        if storage.find_by_value(&value)?.is_some() {
+            if verbose {
+                eprintln!("Skipping duplicate: {}", value);
+            }
+            skipped += 1;
+            continue;
+        }
+
+        let tags = if tags.is_empty() {
+            Vec::new()
+        } else {
+            tags.split(',').map(|t| t.to_string()).collect()
+        };
+
+        let mut tracked = TrackedString::new(value, None, tags);
+        tracked.id = uuid::Uuid::parse_str(id)?;
+        storage.add_string(tracked)?;
+        imported += 1;
+    }
+
+    println!("Imported {} string(s), skipped {} duplicate(s)", imported, skipped);
+
+    Ok(())
+}
+
+/// Renders a completion script or man page from the live `Cli` definition,
+/// so packaging artifacts can never drift from the actual argument surface.
+fn cmd_completions(
+    shell: Option<clap_complete::Shell>,
+    man: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use clap::CommandFactory;
+    use std::io::Write;
+
+    let mut command = Cli::command();
+
+    if man {
+        let page = clap_mangen::Man::new(command.clone());
+        let mut buffer = Vec::new();
+        page.render(&mut buffer)?;
+        std::io::stdout().write_all(&buffer)?;
+    } else if let Some(shell) = shell {
+        let bin_name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, bin_name, &mut std::io::stdout());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Static archive (`.a` / COFF `.lib`) patching.
+//!
+//! An archive is just a container of object-file members in the common Unix
+//! `ar` layout (shared by GNU `.a` and Windows `.lib`/import libraries). We
+//! patch every member that parses as a PE/ELF/Mach-O object with the usual
+//! per-format routines and reassemble the archive around the results, rather
+//! than trying to patch the container format itself.
+
+use super::{elf, macho, pe, PatchError, PatchResult, PatchStrategy};
+use goblin::archive::Archive;
+use goblin::Object;
+
+/// One patched (or passed-through) archive member, in original order.
+struct Member {
+    name: String,
+    data: Vec<u8>,
+}
+
+/// Patch every PE/ELF/Mach-O member of an `ar`/`.lib` archive, and
+/// reassemble the archive. Returns the rebuilt archive bytes plus one
+/// `PatchResult` per member that was actually patched.
+///
+/// `goblin::archive::Archive::members()` only enumerates regular content
+/// members - it parses the leading `/`/`SYM64/` symbol-table member and any
+/// `//` extended-name table into its own `symbols`/`member_array` fields
+/// rather than treating them as members, so neither round-trips through
+/// `build_ar` below. The rebuilt archive therefore has no symbol index:
+/// `ar -t`/content extraction still works, but a linker doing symbol-based
+/// member lookup (`ld`/`lld` pulling an object out of a `.a` to resolve an
+/// undefined symbol) won't find anything until the archive is re-indexed
+/// with `ranlib`/`lib.exe /OUT:`.
+pub fn patch_archive(
+    data: &[u8],
+    string: &str,
+    strategy: PatchStrategy,
+    force: bool,
+) -> Result<(Vec<u8>, Vec<PatchResult>), PatchError> {
+    let archive =
+        Archive::parse(data).map_err(|e| PatchError::PatchFailed(format!("not an archive: {}", e)))?;
+
+    let mut members = Vec::new();
+    let mut results = Vec::new();
+
+    for name in archive.members() {
+        let member_data = archive
+            .extract(name, data)
+            .map_err(|e| PatchError::PatchFailed(format!("failed to read member {}: {}", name, e)))?;
+
+        match patch_member(member_data, string, strategy, force) {
+            Some(Ok((patched_bytes, result))) => {
+                results.push(result);
+                members.push(Member {
+                    name: name.to_string(),
+                    data: patched_bytes,
+                });
+            }
+            // Not a patchable object (non-matching format) or the patch
+            // itself failed (e.g. no cave) - keep the
+            // member as-is rather than failing the whole archive.
+            _ => members.push(Member {
+                name: name.to_string(),
+                data: member_data.to_vec(),
+            }),
+        }
+    }
+
+    if results.is_empty() {
+        return Err(PatchError::PatchFailed(
+            "No patchable object members found in archive".to_string(),
+        ));
+    }
+
+    Ok((build_ar(&members), results))
+}
+
+/// Dispatch a single archive member to the matching format-specific patcher.
+/// Returns `None` for members that aren't a recognized object format at all
+/// (e.g. a plain data file stored in the archive).
+fn patch_member(
+    member_data: &[u8],
+    string: &str,
+    strategy: PatchStrategy,
+    force: bool,
+) -> Option<Result<(Vec<u8>, PatchResult), PatchError>> {
+    match Object::parse(member_data) {
+        Ok(Object::PE(_)) => Some(pe::patch_pe(member_data, string, strategy, force)),
+        Ok(Object::Elf(_)) => Some(elf::patch_elf(member_data, string, strategy)),
+        Ok(Object::Mach(goblin::mach::Mach::Binary(_))) => {
+            Some(macho::patch_macho(member_data, string, strategy))
+        }
+        _ => None,
+    }
+}
+
+/// Reassemble a GNU/System V-style `ar` archive from its members.
+///
+/// We always emit the plain "name/" (System V) form of the member-name field
+/// rather than trying to reproduce a `//` long-name table, so this only
+/// round-trips member names up to 15 characters - true for the overwhelming
+/// majority of `.a`/`.lib` object members. It also never writes a leading
+/// `/`/`SYM64/` symbol-table member, since `members` (built from
+/// `Archive::members()`, see `patch_archive` above) never contains one to
+/// begin with - the output has no symbol index at all.
+fn build_ar(members: &[Member]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8 + members.iter().map(|m| 60 + m.data.len() + 1).sum::<usize>());
+    out.extend_from_slice(b"!<arch>\n");
+
+    for member in members {
+        out.extend_from_slice(&ar_header(&member.name, member.data.len()));
+        out.extend_from_slice(&member.data);
+        if member.data.len() % 2 != 0 {
+            out.push(b'\n'); // members are 2-byte aligned
+        }
+    }
+
+    out
+}
+
+/// Build a single fixed-width 60-byte `ar` member header.
+fn ar_header(name: &str, size: usize) -> [u8; 60] {
+    let mut header = [b' '; 60];
+
+    let name_field = format!("{}/", name);
+    let name_bytes = name_field.as_bytes();
+    let copy_len = name_bytes.len().min(16);
+    header[0..copy_len].copy_from_slice(&name_bytes[..copy_len]);
+
+    write_field(&mut header, 16, "0"); // mtime
+    write_field(&mut header, 28, "0"); // uid
+    write_field(&mut header, 34, "0"); // gid
+    write_field(&mut header, 40, "644"); // mode
+    write_field(&mut header, 48, &size.to_string()); // size
+
+    header[58] = b'`';
+    header[59] = b'\n';
+    header
+}
+
+fn write_field(header: &mut [u8; 60], offset: usize, value: &str) {
+    let bytes = value.as_bytes();
+    header[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
@@ -3,19 +3,198 @@ use super::{PatchError, PatchResult, PatchStrategy};
 use crate::storage::BinaryFormat;
 use goblin::mach::MachO;
 
+/// Fat/universal header + per-arch table are always big-endian, regardless
+/// of the endianness of the thin slices they describe.
+const FAT_MAGIC: u32 = 0xcafebabe;
+const FAT_HEADER_SIZE: usize = 8;
+const FAT_ARCH_SIZE: usize = 20;
+
+struct FatArch {
+    cputype: u32,
+    cpusubtype: u32,
+    offset: u32,
+    size: u32,
+    align: u32,
+}
+
 pub fn patch_macho(
     data: &[u8],
     string: &str,
     strategy: PatchStrategy,
 ) -> Result<(Vec<u8>, PatchResult), PatchError> {
-    let macho = MachO::parse(data, 0)?;
+    patch_macho_bytes(data, string.as_bytes(), strategy)
+}
+
+/// Patch a fat/universal Mach-O: every thin slice is patched independently
+/// with the requested strategy, then the file is rebuilt with a fresh
+/// `fat_arch` table reflecting each slice's new offset/size (slices can grow,
+/// e.g. under `Extend`, so later slices shift).
+pub fn patch_macho_fat(
+    data: &[u8],
+    string: &str,
+    strategy: PatchStrategy,
+) -> Result<(Vec<u8>, PatchResult), PatchError> {
+    let arches = parse_fat_header(data)?;
+
+    let mut patched_slices = Vec::with_capacity(arches.len());
+    let mut summaries = Vec::with_capacity(arches.len());
+
+    for arch in &arches {
+        let start = arch.offset as usize;
+        let end = start
+            .checked_add(arch.size as usize)
+            .filter(|&e| e <= data.len())
+            .ok_or(PatchError::PatchFailed(
+                "fat_arch slice out of bounds".to_string(),
+            ))?;
+
+        let (patched, result) = patch_macho_bytes(&data[start..end], string.as_bytes(), strategy)?;
+        summaries.push(format!(
+            "cputype 0x{:x}: {}{}",
+            arch.cputype,
+            result.strategy_used,
+            match (result.virtual_address, result.file_offset) {
+                (Some(va), Some(off)) => format!(" (va=0x{:x}, slice_offset=0x{:x})", va, off),
+                (None, Some(off)) => format!(" (slice_offset=0x{:x})", off),
+                _ => String::new(),
+            }
+        ));
+        patched_slices.push((arch.cputype, arch.cpusubtype, arch.align, patched));
+    }
+
+    let rebuilt = rebuild_fat(&patched_slices);
+
+    if !verify_fat_patch(&rebuilt, string) {
+        return Err(PatchError::VerificationFailed);
+    }
+
+    Ok((
+        rebuilt,
+        PatchResult {
+            format: BinaryFormat::MachOFat,
+            strategy_used: format!("fat ({} slice(s): {})", summaries.len(), summaries.join("; ")),
+            virtual_address: None,
+            file_offset: None,
+            codec: None,
+            uncompressed_size: None,
+            rs_params: None,
+            signature_stripped: false,
+        },
+    ))
+}
+
+/// Confirm the tracking string landed in *every* thin slice of a fat
+/// binary, not just somewhere in the overall buffer.
+pub fn verify_fat_patch(data: &[u8], string: &str) -> bool {
+    let arches = match parse_fat_header(data) {
+        Ok(arches) => arches,
+        Err(_) => return false,
+    };
+
+    if arches.is_empty() {
+        return false;
+    }
+
     let string_bytes = string.as_bytes();
-    let needed_size = string_bytes.len() + 1;
+    arches.iter().all(|arch| {
+        let start = arch.offset as usize;
+        let end = start.saturating_add(arch.size as usize).min(data.len());
+        start < end
+            && data[start..end]
+                .windows(string_bytes.len())
+                .any(|window| window == string_bytes)
+    })
+}
+
+fn parse_fat_header(data: &[u8]) -> Result<Vec<FatArch>, PatchError> {
+    if data.len() < FAT_HEADER_SIZE {
+        return Err(PatchError::PatchFailed("file too small for fat header".to_string()));
+    }
+
+    let magic = u32::from_be_bytes(data[0..4].try_into().unwrap());
+    if magic != FAT_MAGIC {
+        return Err(PatchError::PatchFailed("not a fat Mach-O (bad magic)".to_string()));
+    }
+
+    let nfat_arch = u32::from_be_bytes(data[4..8].try_into().unwrap()) as usize;
+    let table_end = FAT_HEADER_SIZE + nfat_arch * FAT_ARCH_SIZE;
+    if table_end > data.len() {
+        return Err(PatchError::PatchFailed("fat_arch table out of bounds".to_string()));
+    }
+
+    let mut arches = Vec::with_capacity(nfat_arch);
+    for i in 0..nfat_arch {
+        let entry = &data[FAT_HEADER_SIZE + i * FAT_ARCH_SIZE..FAT_HEADER_SIZE + (i + 1) * FAT_ARCH_SIZE];
+        arches.push(FatArch {
+            cputype: u32::from_be_bytes(entry[0..4].try_into().unwrap()),
+            cpusubtype: u32::from_be_bytes(entry[4..8].try_into().unwrap()),
+            offset: u32::from_be_bytes(entry[8..12].try_into().unwrap()),
+            size: u32::from_be_bytes(entry[12..16].try_into().unwrap()),
+            align: u32::from_be_bytes(entry[16..20].try_into().unwrap()),
+        });
+    }
+
+    Ok(arches)
+}
+
+/// Rebuild a fat binary from its (possibly resized) patched slices, laying
+/// each one out at its required `2^align` boundary and writing a fresh
+/// `fat_arch` table with the recomputed offsets/sizes.
+fn rebuild_fat(slices: &[(u32, u32, u32, Vec<u8>)]) -> Vec<u8> {
+    let header_and_table_size = FAT_HEADER_SIZE + slices.len() * FAT_ARCH_SIZE;
+
+    let mut arches = Vec::with_capacity(slices.len());
+    let mut cursor = header_and_table_size;
+    for (cputype, cpusubtype, align, data) in slices {
+        let alignment = 1usize << *align;
+        cursor = align_up(cursor, alignment);
+        arches.push(FatArch {
+            cputype: *cputype,
+            cpusubtype: *cpusubtype,
+            offset: cursor as u32,
+            size: data.len() as u32,
+            align: *align,
+        });
+        cursor += data.len();
+    }
+
+    let mut out = vec![0u8; cursor];
+    out[0..4].copy_from_slice(&FAT_MAGIC.to_be_bytes());
+    out[4..8].copy_from_slice(&(slices.len() as u32).to_be_bytes());
+
+    for (i, arch) in arches.iter().enumerate() {
+        let entry_off = FAT_HEADER_SIZE + i * FAT_ARCH_SIZE;
+        out[entry_off..entry_off + 4].copy_from_slice(&arch.cputype.to_be_bytes());
+        out[entry_off + 4..entry_off + 8].copy_from_slice(&arch.cpusubtype.to_be_bytes());
+        out[entry_off + 8..entry_off + 12].copy_from_slice(&arch.offset.to_be_bytes());
+        out[entry_off + 12..entry_off + 16].copy_from_slice(&arch.size.to_be_bytes());
+        out[entry_off + 16..entry_off + 20].copy_from_slice(&arch.align.to_be_bytes());
+
+        let (_, _, _, slice_data) = &slices[i];
+        let start = arch.offset as usize;
+        out[start..start + slice_data.len()].copy_from_slice(slice_data);
+    }
+
+    out
+}
+
+/// Same as `patch_macho`, but for an arbitrary byte payload rather than a
+/// UTF-8 string (e.g. a compressed/framed blob from the payload subsystem).
+pub fn patch_macho_bytes(
+    data: &[u8],
+    bytes: &[u8],
+    strategy: PatchStrategy,
+) -> Result<(Vec<u8>, PatchResult), PatchError> {
+    let macho = MachO::parse(data, 0)?;
+    let needed_size = bytes.len() + 1;
 
     match strategy {
-        PatchStrategy::Cave => patch_macho_cave(data, &macho, string_bytes, needed_size),
-        PatchStrategy::Section => patch_macho_section(data, &macho, string_bytes),
-        PatchStrategy::Extend => patch_macho_extend(data, &macho, string_bytes),
+        PatchStrategy::Cave => patch_macho_cave(data, &macho, bytes, needed_size),
+        PatchStrategy::Section => patch_macho_section(data, &macho, bytes),
+        PatchStrategy::Extend => patch_macho_extend(data, &macho, bytes),
+        PatchStrategy::Note => Err(PatchError::PatchFailed(
+            "Note strategy is only supported for ELF binaries".to_string(),
+        )),
         PatchStrategy::Overlay => unreachable!("Overlay is handled in mod.rs"),
     }
 }
@@ -87,6 +266,10 @@ fn patch_macho_cave(
                     strategy_used: format!("cave ({})", best_section_name),
                     virtual_address: va,
                     file_offset: Some(cave.file_offset as u64),
+                    codec: None,
+                    uncompressed_size: None,
+                    rs_params: None,
+                    signature_stripped: false,
                 },
             ))
         }
@@ -126,8 +309,14 @@ fn patch_macho_section(
     // Write string
     patched[write_offset..write_offset + string_bytes.len()].copy_from_slice(string_bytes);
 
-    // Note: A full implementation would update the segment's filesize
-    // This simplified version just appends data
+    // The file just grew past __LINKEDIT's old boundary, so its
+    // LC_SEGMENT(_64) load command's on-disk filesize/vmsize are now stale;
+    // bump them the same way the Extend strategy does, or the load
+    // commands no longer match the actual file layout.
+    let grown_by = patched.len() as u64 - (linkedit.fileoff + linkedit.filesize);
+    if let Some(cmd_offset) = find_segment_command_offset(data, macho.is_64, "__LINKEDIT") {
+        bump_segment_command_size(&mut patched, cmd_offset, macho.is_64, grown_by);
+    }
 
     Ok((
         patched,
@@ -140,6 +329,10 @@ fn patch_macho_section(
             strategy_used: "section (__LINKEDIT extension)".to_string(),
             virtual_address: None,
             file_offset: Some(write_offset as u64),
+            codec: None,
+            uncompressed_size: None,
+            rs_params: None,
+            signature_stripped: false,
         },
     ))
 }
@@ -149,20 +342,22 @@ fn patch_macho_extend(
     macho: &MachO,
     string_bytes: &[u8],
 ) -> Result<(Vec<u8>, PatchResult), PatchError> {
-    // Extend __DATA segment's last section
+    // Always write at true EOF and grow whichever segment's file range
+    // actually ends there. `__DATA` is *not* reliably the last segment on
+    // disk - the normal layout is __TEXT, __DATA, __LINKEDIT, so
+    // __LINKEDIT's symbol/string tables and code signature immediately
+    // follow __DATA. Picking __DATA by name would compute a write offset
+    // mid-file and then truncate everything after it on `resize`.
     let mut patched = data.to_vec();
 
-    // Find __DATA segment
-    let data_segment = macho
+    let last_segment = macho
         .segments
         .iter()
-        .find(|s| s.name().unwrap_or("") == "__DATA")
-        .or_else(|| macho.segments.iter().last())
+        .max_by_key(|s| s.fileoff + s.filesize)
         .ok_or(PatchError::PatchFailed("No segment found".to_string()))?;
 
-    let seg_name = data_segment.name().unwrap_or("unknown");
-    let segment_end = (data_segment.fileoff + data_segment.filesize) as usize;
-    let write_offset = segment_end;
+    let seg_name = last_segment.name().unwrap_or("unknown");
+    let write_offset = data.len();
 
     // Extend file
     patched.resize(write_offset + string_bytes.len() + 1, 0);
@@ -170,7 +365,19 @@ fn patch_macho_extend(
     // Write string
     patched[write_offset..write_offset + string_bytes.len()].copy_from_slice(string_bytes);
 
-    let va = data_segment.vmaddr + data_segment.vmsize;
+    // The file just grew past this segment's old boundary, so its
+    // LC_SEGMENT(_64) load command's on-disk filesize/vmsize are now stale.
+    // Grow it to cover all the way through the new EOF (not just by the
+    // appended string's length), in case the segment's declared file range
+    // didn't already reach the old EOF exactly. Walk the raw load commands
+    // (rather than trust goblin's in-memory offsets, which describe `data`
+    // and not `patched`) to find and bump the matching command in place.
+    let grown_by = patched.len() as u64 - (last_segment.fileoff + last_segment.filesize);
+    if let Some(cmd_offset) = find_segment_command_offset(data, macho.is_64, seg_name) {
+        bump_segment_command_size(&mut patched, cmd_offset, macho.is_64, grown_by);
+    }
+
+    let va = last_segment.vmaddr + last_segment.vmsize;
 
     Ok((
         patched,
@@ -183,10 +390,81 @@ fn patch_macho_extend(
             strategy_used: format!("extend ({})", seg_name),
             virtual_address: Some(va),
             file_offset: Some(write_offset as u64),
+            codec: None,
+            uncompressed_size: None,
+            rs_params: None,
+            signature_stripped: false,
         },
     ))
 }
 
+const LC_SEGMENT: u32 = 0x1;
+const LC_SEGMENT_64: u32 = 0x19;
+
+/// Scan the load commands following the mach_header(_64) for the
+/// LC_SEGMENT(_64) command whose segname matches `target`, returning the
+/// file offset where that command begins. Walked by hand off the raw header
+/// fields (`ncmds`, each command's `cmd`/`cmdsize`) rather than through
+/// goblin, since we need the exact on-disk byte offset to patch in place.
+fn find_segment_command_offset(data: &[u8], is_64: bool, target: &str) -> Option<usize> {
+    let header_size = if is_64 { 32 } else { 28 };
+    if data.len() < header_size {
+        return None;
+    }
+
+    let ncmds = u32::from_le_bytes(data[16..20].try_into().ok()?);
+    let segment_cmd = if is_64 { LC_SEGMENT_64 } else { LC_SEGMENT };
+    let target = target.as_bytes();
+
+    let mut offset = header_size;
+    for _ in 0..ncmds {
+        if offset + 8 > data.len() {
+            break;
+        }
+        let cmd = u32::from_le_bytes(data[offset..offset + 4].try_into().ok()?);
+        let cmdsize = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().ok()?) as usize;
+
+        if cmd == segment_cmd {
+            let segname_end = offset + 24;
+            if segname_end <= data.len() {
+                let segname = &data[offset + 8..segname_end];
+                if segname.split(|&b| b == 0).next().unwrap_or(&[]) == target {
+                    return Some(offset);
+                }
+            }
+        }
+
+        if cmdsize == 0 {
+            break;
+        }
+        offset += cmdsize;
+    }
+
+    None
+}
+
+/// Add `grown_by` to both `vmsize` and `filesize` of the LC_SEGMENT(_64)
+/// command at `cmd_offset`, per the field layout in mach-o/loader.h.
+fn bump_segment_command_size(patched: &mut [u8], cmd_offset: usize, is_64: bool, grown_by: u64) {
+    if is_64 {
+        // segment_command_64: vmaddr@+24, vmsize@+32, fileoff@+40, filesize@+48
+        let vmsize_off = cmd_offset + 24 + 8;
+        let filesize_off = vmsize_off + 16;
+        for off in [vmsize_off, filesize_off] {
+            let current = u64::from_le_bytes(patched[off..off + 8].try_into().unwrap());
+            patched[off..off + 8].copy_from_slice(&(current + grown_by).to_le_bytes());
+        }
+    } else {
+        // segment_command: vmaddr@+24, vmsize@+28, fileoff@+32, filesize@+36
+        let vmsize_off = cmd_offset + 24 + 4;
+        let filesize_off = vmsize_off + 8;
+        for off in [vmsize_off, filesize_off] {
+            let current = u32::from_le_bytes(patched[off..off + 4].try_into().unwrap()) as u64;
+            patched[off..off + 4].copy_from_slice(&((current + grown_by) as u32).to_le_bytes());
+        }
+    }
+}
+
 fn calculate_va_from_offset(macho: &MachO, file_offset: usize) -> Option<u64> {
     for segment in &macho.segments {
         let seg_start = segment.fileoff as usize;
@@ -207,3 +485,72 @@ fn align_up(value: usize, alignment: usize) -> usize {
     }
     (value + alignment - 1) & !(alignment - 1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal mach_header_64 (32 bytes) followed by one segment_command_64
+    /// (72 bytes, no sections) named `__DATA`, with known vmaddr/vmsize/
+    /// fileoff/filesize so a bump can be checked field-by-field.
+    fn segment_command_64_fixture() -> Vec<u8> {
+        let mut data = vec![0u8; 32 + 72];
+
+        data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+
+        let cmd_off = 32;
+        data[cmd_off..cmd_off + 4].copy_from_slice(&LC_SEGMENT_64.to_le_bytes());
+        data[cmd_off + 4..cmd_off + 8].copy_from_slice(&72u32.to_le_bytes()); // cmdsize
+        data[cmd_off + 8..cmd_off + 14].copy_from_slice(b"__DATA");
+        data[cmd_off + 24..cmd_off + 32].copy_from_slice(&0x1000u64.to_le_bytes()); // vmaddr
+        data[cmd_off + 32..cmd_off + 40].copy_from_slice(&0x2000u64.to_le_bytes()); // vmsize
+        data[cmd_off + 40..cmd_off + 48].copy_from_slice(&0x4000u64.to_le_bytes()); // fileoff
+        data[cmd_off + 48..cmd_off + 56].copy_from_slice(&0x2000u64.to_le_bytes()); // filesize
+
+        data
+    }
+
+    #[test]
+    fn test_bump_segment_command_size_64_leaves_fileoff_unchanged() {
+        let mut data = segment_command_64_fixture();
+        let cmd_off = find_segment_command_offset(&data, true, "__DATA").unwrap();
+
+        bump_segment_command_size(&mut data, cmd_off, true, 0x100);
+
+        let vmsize = u64::from_le_bytes(data[cmd_off + 32..cmd_off + 40].try_into().unwrap());
+        let fileoff = u64::from_le_bytes(data[cmd_off + 40..cmd_off + 48].try_into().unwrap());
+        let filesize = u64::from_le_bytes(data[cmd_off + 48..cmd_off + 56].try_into().unwrap());
+
+        assert_eq!(vmsize, 0x2100);
+        assert_eq!(fileoff, 0x4000, "fileoff must not move when only growing the segment");
+        assert_eq!(filesize, 0x2100);
+    }
+
+    #[test]
+    fn test_bump_segment_command_size_32_leaves_fileoff_unchanged() {
+        let mut data = vec![0u8; 28 + 56];
+        data[16..20].copy_from_slice(&1u32.to_le_bytes()); // ncmds
+
+        let cmd_off = 28;
+        data[cmd_off..cmd_off + 4].copy_from_slice(&LC_SEGMENT.to_le_bytes());
+        data[cmd_off + 4..cmd_off + 8].copy_from_slice(&56u32.to_le_bytes()); // cmdsize
+        data[cmd_off + 8..cmd_off + 14].copy_from_slice(b"__DATA");
+        data[cmd_off + 24..cmd_off + 28].copy_from_slice(&0x1000u32.to_le_bytes()); // vmaddr
+        data[cmd_off + 28..cmd_off + 32].copy_from_slice(&0x2000u32.to_le_bytes()); // vmsize
+        data[cmd_off + 32..cmd_off + 36].copy_from_slice(&0x4000u32.to_le_bytes()); // fileoff
+        data[cmd_off + 36..cmd_off + 40].copy_from_slice(&0x2000u32.to_le_bytes()); // filesize
+
+        let found_off = find_segment_command_offset(&data, false, "__DATA").unwrap();
+        assert_eq!(found_off, cmd_off);
+
+        bump_segment_command_size(&mut data, cmd_off, false, 0x100);
+
+        let vmsize = u32::from_le_bytes(data[cmd_off + 28..cmd_off + 32].try_into().unwrap());
+        let fileoff = u32::from_le_bytes(data[cmd_off + 32..cmd_off + 36].try_into().unwrap());
+        let filesize = u32::from_le_bytes(data[cmd_off + 36..cmd_off + 40].try_into().unwrap());
+
+        assert_eq!(vmsize, 0x2100);
+        assert_eq!(fileoff, 0x4000, "fileoff must not move when only growing the segment");
+        assert_eq!(filesize, 0x2100);
+    }
+}
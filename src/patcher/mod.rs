@@ -1,7 +1,10 @@
+pub mod archive;
 pub mod cave;
 pub mod elf;
 pub mod macho;
+pub mod payload;
 pub mod pe;
+pub mod resilient;
 
 use crate::storage::BinaryFormat;
 use goblin::Object;
@@ -38,6 +41,15 @@ pub enum PatchError {
 
     #[error("Binary verification failed")]
     VerificationFailed,
+
+    #[error("Failed to recover tracking string: {0}")]
+    RecoveryFailed(String),
+
+    #[error(
+        "Binary is Authenticode-signed; patching would invalidate its signature \
+         (pass --force to strip the certificate table instead)"
+    )]
+    SignedBinary,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,6 +58,9 @@ pub enum PatchStrategy {
     Section,
     Extend,
     Overlay,
+    /// ELF-only: embed the string in a well-formed `SHT_NOTE`/`PT_NOTE`
+    /// note entry instead of raw bytes. See `elf::patch_elf_note`.
+    Note,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -56,6 +71,7 @@ impl From<crate::cli::PatchStrategy> for PatchStrategy {
             crate::cli::PatchStrategy::Section => PatchStrategy::Section,
             crate::cli::PatchStrategy::Extend => PatchStrategy::Extend,
             crate::cli::PatchStrategy::Overlay => PatchStrategy::Overlay,
+            crate::cli::PatchStrategy::Note => PatchStrategy::Note,
         }
     }
 }
@@ -66,6 +82,37 @@ pub struct PatchResult {
     pub strategy_used: String,
     pub virtual_address: Option<u64>,
     pub file_offset: Option<u64>,
+    /// Set when the patch carried a compressed payload frame rather than a
+    /// plain string, recording the codec and the uncompressed size.
+    pub codec: Option<payload::Codec>,
+    pub uncompressed_size: Option<u64>,
+    /// Set when the patch wrapped the string in a Reed-Solomon RS(n, k)
+    /// frame, recording the layout `recover` needs to locate and decode it.
+    pub rs_params: Option<(u8, u8)>,
+    /// Set when the target was an Authenticode-signed PE and `--force`
+    /// opted into stripping its certificate table so the patch could
+    /// proceed. See `pe::patch_pe_bytes`.
+    pub signature_stripped: bool,
+}
+
+impl PatchResult {
+    fn string_result(
+        format: BinaryFormat,
+        strategy_used: String,
+        virtual_address: Option<u64>,
+        file_offset: Option<u64>,
+    ) -> Self {
+        Self {
+            format,
+            strategy_used,
+            virtual_address,
+            file_offset,
+            codec: None,
+            uncompressed_size: None,
+            rs_params: None,
+            signature_stripped: false,
+        }
+    }
 }
 
 pub struct BinaryPatcher;
@@ -98,27 +145,54 @@ impl BinaryPatcher {
                 }
                 goblin::mach::Mach::Fat(_) => Ok(BinaryFormat::MachOFat),
             },
+            Object::Archive(_) => Ok(BinaryFormat::Archive),
             _ => Ok(BinaryFormat::Unknown),
         }
     }
 
     /// Patch a binary buffer in memory (WASM-compatible)
     /// Returns the patched binary data and patch result
+    ///
+    /// `force` only affects signed PE targets: by default a patch to an
+    /// Authenticode-signed PE is refused (`PatchError::SignedBinary`) rather
+    /// than silently producing a binary whose signature no longer matches
+    /// its contents; `force` opts into stripping the certificate table
+    /// instead. See `pe::patch_pe_bytes`.
     pub fn patch_buffer(
         data: &[u8],
         string: &str,
         strategy: PatchStrategy,
+        force: bool,
     ) -> Result<(Vec<u8>, PatchResult), PatchError> {
         let format = Self::detect_format(data)?;
 
+        // Archives patch a variable number of members and report one result
+        // per member; use `patch_archive_buffer` for that detail. Here we
+        // still support the single-result contract by summarizing.
+        if format == BinaryFormat::Archive {
+            let (patched_data, results) = Self::patch_archive_buffer(data, string, strategy, force)?;
+            let summary = PatchResult::string_result(
+                BinaryFormat::Archive,
+                format!(
+                    "archive ({} member(s) patched; no symbol index, re-run ranlib/lib.exe if the archive is linked by symbol)",
+                    results.len()
+                ),
+                None,
+                None,
+            );
+            return Ok((patched_data, summary));
+        }
+
         // Handle overlay strategy universally (doesn't need format-specific handling)
         if strategy == PatchStrategy::Overlay {
-            return Self::patch_overlay(data, string, format);
+            let mut payload = string.as_bytes().to_vec();
+            payload.push(0); // null terminator
+            return Self::patch_overlay(data, &payload, format, force);
         }
 
         let (patched_data, result) = match format {
             BinaryFormat::PE32 | BinaryFormat::PE64 => {
-                pe::patch_pe(data, string, strategy)?
+                pe::patch_pe(data, string, strategy, force)?
             }
             BinaryFormat::ELF32 | BinaryFormat::ELF64 => {
                 elf::patch_elf(data, string, strategy)?
@@ -127,10 +201,12 @@ impl BinaryPatcher {
                 macho::patch_macho(data, string, strategy)?
             }
             BinaryFormat::MachOFat => {
-                return Err(PatchError::PatchFailed(
-                    "Fat/Universal binaries not yet supported".to_string(),
-                ));
+                // `macho::patch_macho_fat` verifies the string landed in
+                // every slice itself, so return straight away rather than
+                // falling through to the whole-buffer `verify_patch` below.
+                return macho::patch_macho_fat(data, string, strategy);
             }
+            BinaryFormat::Archive => unreachable!("handled above"),
             BinaryFormat::Unknown => return Err(PatchError::UnsupportedFormat),
         };
 
@@ -142,28 +218,169 @@ impl BinaryPatcher {
         Ok((patched_data, result))
     }
 
-    /// Patch using overlay strategy (append to end of file)
+    /// Patch using overlay strategy (append `payload` to end of file).
+    ///
+    /// For a PE target this runs the same Authenticode check every other PE
+    /// strategy goes through (`pe::find_security_directory`): a signed PE is
+    /// refused unless `force`, in which case the certificate table is
+    /// stripped before the payload is appended so the result doesn't keep a
+    /// now-invalid signature lying around.
     fn patch_overlay(
         data: &[u8],
-        string: &str,
+        payload: &[u8],
         format: BinaryFormat,
+        force: bool,
     ) -> Result<(Vec<u8>, PatchResult), PatchError> {
-        let string_bytes = string.as_bytes();
-        let file_offset = data.len();
-
-        let mut patched = data.to_vec();
-        patched.extend_from_slice(string_bytes);
-        patched.push(0); // null terminator
-
-        Ok((
-            patched,
-            PatchResult {
-                format,
-                strategy_used: "overlay".to_string(),
-                virtual_address: None, // Overlay data isn't mapped to VA
-                file_offset: Some(file_offset as u64),
-            },
-        ))
+        let mut stripped;
+        let mut base = data;
+        let mut signature_stripped = false;
+
+        if matches!(format, BinaryFormat::PE32 | BinaryFormat::PE64) {
+            let pe = goblin::pe::PE::parse(data)?;
+            if let Some((dir_entry_offset, cert_offset, _cert_size)) =
+                pe::find_security_directory(data, &pe)
+            {
+                if !force {
+                    return Err(PatchError::SignedBinary);
+                }
+                stripped = data.to_vec();
+                stripped.truncate(cert_offset as usize);
+                stripped[dir_entry_offset..dir_entry_offset + 8].copy_from_slice(&[0u8; 8]);
+                base = &stripped;
+                signature_stripped = true;
+            }
+        }
+
+        let file_offset = base.len();
+        let mut patched = base.to_vec();
+        patched.extend_from_slice(payload);
+
+        let mut result = PatchResult::string_result(
+            format,
+            "overlay".to_string(),
+            None, // Overlay data isn't mapped to VA
+            Some(file_offset as u64),
+        );
+        result.signature_stripped = signature_stripped;
+
+        Ok((patched, result))
+    }
+
+    /// Patch a binary buffer with an arbitrary file payload instead of a
+    /// short tracking string. The payload is compressed with `codec` and
+    /// wrapped in a small framed header (magic + lengths + codec id) before
+    /// being placed with the requested strategy, so `CaveFinder` sizes the
+    /// search to the *compressed* bytes rather than the raw payload.
+    pub fn patch_buffer_payload(
+        data: &[u8],
+        payload: &[u8],
+        codec: payload::Codec,
+        strategy: PatchStrategy,
+    ) -> Result<(Vec<u8>, PatchResult), PatchError> {
+        let format = Self::detect_format(data)?;
+        let compressed = payload::compress(payload, codec)?;
+        let framed = payload::frame(&compressed, codec, payload.len());
+
+        let (patched_data, mut result) = if strategy == PatchStrategy::Overlay {
+            // Not wired to a `--force` flag at this entry point yet, so a
+            // signed PE always refuses an overlay payload patch too.
+            Self::patch_overlay(data, &framed, format, false)?
+        } else {
+            match format {
+                // Not wired to a `--force` flag at this entry point yet, so
+                // a signed PE always refuses a payload patch.
+                BinaryFormat::PE32 | BinaryFormat::PE64 => {
+                    pe::patch_pe_bytes(data, &framed, strategy, false)?
+                }
+                BinaryFormat::ELF32 | BinaryFormat::ELF64 => {
+                    elf::patch_elf_bytes(data, &framed, strategy)?
+                }
+                BinaryFormat::MachO32 | BinaryFormat::MachO64 => {
+                    macho::patch_macho_bytes(data, &framed, strategy)?
+                }
+                BinaryFormat::MachOFat => {
+                    return Err(PatchError::PatchFailed(
+                        "Fat/Universal binaries not yet supported".to_string(),
+                    ));
+                }
+                BinaryFormat::Archive | BinaryFormat::Unknown => {
+                    return Err(PatchError::UnsupportedFormat)
+                }
+            }
+        };
+
+        result.strategy_used = format!("{} (payload)", result.strategy_used);
+        result.codec = Some(codec);
+        result.uncompressed_size = Some(payload.len() as u64);
+
+        if !payload::verify_frame(&patched_data) {
+            return Err(PatchError::VerificationFailed);
+        }
+
+        Ok((patched_data, result))
+    }
+
+    /// Patch a binary buffer with the tracking string wrapped in a
+    /// Reed-Solomon RS(n, k) frame instead of embedding it raw, so `recover`
+    /// can reconstruct it even after partial overwrites or stripping. `n`
+    /// is the total codeword symbols and `k` the data symbols the string is
+    /// padded into; see `resilient` for the correction budget this buys.
+    pub fn patch_buffer_resilient(
+        data: &[u8],
+        string: &str,
+        n: u8,
+        k: u8,
+        strategy: PatchStrategy,
+        force: bool,
+    ) -> Result<(Vec<u8>, PatchResult), PatchError> {
+        let format = Self::detect_format(data)?;
+        let framed = resilient::frame(string, n, k)?;
+
+        let (patched_data, mut result) = if strategy == PatchStrategy::Overlay {
+            Self::patch_overlay(data, &framed, format, force)?
+        } else {
+            match format {
+                BinaryFormat::PE32 | BinaryFormat::PE64 => {
+                    pe::patch_pe_bytes(data, &framed, strategy, force)?
+                }
+                BinaryFormat::ELF32 | BinaryFormat::ELF64 => {
+                    elf::patch_elf_bytes(data, &framed, strategy)?
+                }
+                BinaryFormat::MachO32 | BinaryFormat::MachO64 => {
+                    macho::patch_macho_bytes(data, &framed, strategy)?
+                }
+                BinaryFormat::MachOFat => {
+                    return Err(PatchError::PatchFailed(
+                        "Fat/Universal binaries not yet supported".to_string(),
+                    ));
+                }
+                BinaryFormat::Archive | BinaryFormat::Unknown => {
+                    return Err(PatchError::UnsupportedFormat)
+                }
+            }
+        };
+
+        result.strategy_used = format!("{} (resilient)", result.strategy_used);
+        result.rs_params = Some((n, k));
+
+        if !resilient::verify_frame(&patched_data) {
+            return Err(PatchError::VerificationFailed);
+        }
+
+        Ok((patched_data, result))
+    }
+
+    /// Patch every PE/ELF/Mach-O object member inside an `ar`/`.lib` static
+    /// archive, returning the rebuilt archive and one `PatchResult` per
+    /// member that was actually patched. Use this instead of `patch_buffer`
+    /// when per-member detail is needed rather than a single summary result.
+    pub fn patch_archive_buffer(
+        data: &[u8],
+        string: &str,
+        strategy: PatchStrategy,
+        force: bool,
+    ) -> Result<(Vec<u8>, Vec<PatchResult>), PatchError> {
+        archive::patch_archive(data, string, strategy, force)
     }
 
     /// Verify that the string was successfully injected
@@ -173,6 +390,17 @@ impl BinaryPatcher {
             .any(|window| window == string_bytes)
     }
 
+    /// Verify that a compressed payload frame was successfully injected
+    pub fn verify_payload_patch(data: &[u8]) -> bool {
+        payload::verify_frame(data)
+    }
+
+    /// Verify that a resilient Reed-Solomon frame was successfully injected
+    /// and is currently decodable as-is (before any later corruption)
+    pub fn verify_resilient_patch(data: &[u8]) -> bool {
+        resilient::verify_frame(data)
+    }
+
     /// Patch a binary file on disk (CLI only, not available in WASM)
     #[cfg(not(target_arch = "wasm32"))]
     pub fn patch(
@@ -180,14 +408,42 @@ impl BinaryPatcher {
         output_path: &Path,
         string: &str,
         strategy: PatchStrategy,
-        _force: bool,
+        force: bool,
     ) -> Result<PatchResult, PatchError> {
         let data = fs::read(binary_path)?;
-        let (patched_data, result) = Self::patch_buffer(&data, string, strategy)?;
+        let (patched_data, result) = Self::patch_buffer(&data, string, strategy, force)?;
         fs::write(output_path, &patched_data)?;
         Ok(result)
     }
 
+    /// Patch a binary file on disk with a Reed-Solomon-resilient frame
+    /// (CLI only, not available in WASM)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn patch_resilient(
+        binary_path: &Path,
+        output_path: &Path,
+        string: &str,
+        n: u8,
+        k: u8,
+        strategy: PatchStrategy,
+        force: bool,
+    ) -> Result<PatchResult, PatchError> {
+        let data = fs::read(binary_path)?;
+        let (patched_data, result) =
+            Self::patch_buffer_resilient(&data, string, n, k, strategy, force)?;
+        fs::write(output_path, &patched_data)?;
+        Ok(result)
+    }
+
+    /// Reconstruct a tracking string from a Reed-Solomon-resilient patch in
+    /// a (possibly damaged) binary file on disk (CLI only, not available in
+    /// WASM)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn recover(binary_path: &Path) -> Result<resilient::Recovered, PatchError> {
+        let data = fs::read(binary_path)?;
+        resilient::find_and_recover(&data)
+    }
+
     /// Create a patched binary record for storage (CLI only)
     #[cfg(not(target_arch = "wasm32"))]
     pub fn create_patched_binary_record(
@@ -202,6 +458,11 @@ impl BinaryPatcher {
             strategy: result.strategy_used.clone(),
             virtual_address: result.virtual_address,
             file_offset: result.file_offset,
+            codec: result.codec.map(|c| c.to_string()),
+            uncompressed_size: result.uncompressed_size,
+            rs_n: result.rs_params.map(|(n, _)| n),
+            rs_k: result.rs_params.map(|(_, k)| k),
+            signature_stripped: result.signature_stripped,
             patched_at: Utc::now(),
         }
     }
@@ -0,0 +1,213 @@
+//! Compressed arbitrary-file payload framing.
+//!
+//! Lets the patcher embed a whole file rather than a short tracking string,
+//! compressing it first so larger payloads actually fit in a code cave.
+
+use super::PatchError;
+
+/// Marks the start of a framed payload so `find_frame`/`verify_frame` can
+/// locate and bound it inside a scanned buffer.
+const PAYLOAD_MAGIC: &[u8; 4] = b"BRCP";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// No compression; used for payloads too small to benefit.
+    None,
+    Zstd,
+    Bzip2,
+    Lzma,
+}
+
+impl Codec {
+    fn id(self) -> u8 {
+        match self {
+            Codec::None => 0,
+            Codec::Zstd => 1,
+            Codec::Bzip2 => 2,
+            Codec::Lzma => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Codec::None),
+            1 => Some(Codec::Zstd),
+            2 => Some(Codec::Bzip2),
+            3 => Some(Codec::Lzma),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Codec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Codec::None => write!(f, "none"),
+            Codec::Zstd => write!(f, "zstd"),
+            Codec::Bzip2 => write!(f, "bzip2"),
+            Codec::Lzma => write!(f, "lzma"),
+        }
+    }
+}
+
+/// A decoded `BRCP` frame: the codec it was compressed with, the original
+/// (uncompressed) length, and the compressed payload bytes.
+pub struct Frame {
+    pub codec: Codec,
+    pub original_len: usize,
+    pub compressed: Vec<u8>,
+}
+
+pub fn compress(data: &[u8], codec: Codec) -> Result<Vec<u8>, PatchError> {
+    match codec {
+        Codec::None => Ok(data.to_vec()),
+        Codec::Zstd => zstd::stream::encode_all(data, 0)
+            .map_err(|e| PatchError::PatchFailed(format!("zstd compression failed: {}", e))),
+        Codec::Bzip2 => {
+            use bzip2::write::BzEncoder;
+            use bzip2::Compression;
+            use std::io::Write;
+
+            let mut encoder = BzEncoder::new(Vec::new(), Compression::best());
+            encoder
+                .write_all(data)
+                .map_err(|e| PatchError::PatchFailed(format!("bzip2 compression failed: {}", e)))?;
+            encoder
+                .finish()
+                .map_err(|e| PatchError::PatchFailed(format!("bzip2 compression failed: {}", e)))
+        }
+        Codec::Lzma => {
+            use std::io::Write;
+            use xz2::write::XzEncoder;
+
+            let mut encoder = XzEncoder::new(Vec::new(), 6);
+            encoder
+                .write_all(data)
+                .map_err(|e| PatchError::PatchFailed(format!("lzma compression failed: {}", e)))?;
+            encoder
+                .finish()
+                .map_err(|e| PatchError::PatchFailed(format!("lzma compression failed: {}", e)))
+        }
+    }
+}
+
+pub fn decompress(data: &[u8], codec: Codec, original_len: usize) -> Result<Vec<u8>, PatchError> {
+    match codec {
+        Codec::None => Ok(data.to_vec()),
+        Codec::Zstd => zstd::stream::decode_all(data)
+            .map_err(|e| PatchError::PatchFailed(format!("zstd decompression failed: {}", e))),
+        Codec::Bzip2 => {
+            use bzip2::read::BzDecoder;
+            use std::io::Read;
+
+            let mut decoder = BzDecoder::new(data);
+            let mut out = Vec::with_capacity(original_len);
+            decoder
+                .read_to_end(&mut out)
+                .map_err(|e| PatchError::PatchFailed(format!("bzip2 decompression failed: {}", e)))?;
+            Ok(out)
+        }
+        Codec::Lzma => {
+            use std::io::Read;
+            use xz2::read::XzDecoder;
+
+            let mut decoder = XzDecoder::new(data);
+            let mut out = Vec::with_capacity(original_len);
+            decoder
+                .read_to_end(&mut out)
+                .map_err(|e| PatchError::PatchFailed(format!("lzma decompression failed: {}", e)))?;
+            Ok(out)
+        }
+    }
+}
+
+/// Frame a compressed payload as `magic | codec(1) | original_len(4) |
+/// compressed_len(4) | compressed bytes`.
+pub fn frame(compressed: &[u8], codec: Codec, original_len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + 1 + 4 + 4 + compressed.len());
+    out.extend_from_slice(PAYLOAD_MAGIC);
+    out.push(codec.id());
+    out.extend_from_slice(&(original_len as u32).to_le_bytes());
+    out.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+    out.extend_from_slice(compressed);
+    out
+}
+
+/// Locate and parse a `BRCP` frame anywhere in `data`, returning the frame
+/// plus the byte offset it starts at.
+pub fn find_frame(data: &[u8]) -> Option<(usize, Frame)> {
+    let header_len = 4 + 1 + 4 + 4;
+    let offset = data
+        .windows(PAYLOAD_MAGIC.len())
+        .position(|w| w == PAYLOAD_MAGIC)?;
+
+    if offset + header_len > data.len() {
+        return None;
+    }
+
+    let codec = Codec::from_id(data[offset + 4])?;
+    let original_len = u32::from_le_bytes(data[offset + 5..offset + 9].try_into().ok()?) as usize;
+    let compressed_len =
+        u32::from_le_bytes(data[offset + 9..offset + 13].try_into().ok()?) as usize;
+
+    let body_start = offset + header_len;
+    let body_end = body_start.checked_add(compressed_len)?;
+    if body_end > data.len() {
+        return None;
+    }
+
+    Some((
+        offset,
+        Frame {
+            codec,
+            original_len,
+            compressed: data[body_start..body_end].to_vec(),
+        },
+    ))
+}
+
+/// Check that a `BRCP` frame is present and well-formed, the way
+/// `BinaryPatcher::verify_patch` checks for a raw string substring.
+pub fn verify_frame(data: &[u8]) -> bool {
+    find_frame(data).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip_none() {
+        let payload = b"arbitrary file contents".to_vec();
+        let compressed = compress(&payload, Codec::None).unwrap();
+        let framed = frame(&compressed, Codec::None, payload.len());
+
+        let (offset, parsed) = find_frame(&framed).unwrap();
+        assert_eq!(offset, 0);
+        assert_eq!(parsed.codec, Codec::None);
+        assert_eq!(parsed.original_len, payload.len());
+
+        let decoded = decompress(&parsed.compressed, parsed.codec, parsed.original_len).unwrap();
+        assert_eq!(decoded, payload);
+    }
+
+    #[test]
+    fn test_find_frame_amid_other_bytes() {
+        let payload = b"hello world".to_vec();
+        let compressed = compress(&payload, Codec::None).unwrap();
+        let framed = frame(&compressed, Codec::None, payload.len());
+
+        let mut buffer = vec![0u8; 16];
+        buffer.extend_from_slice(&framed);
+        buffer.extend_from_slice(&[0u8; 8]);
+
+        let (offset, parsed) = find_frame(&buffer).unwrap();
+        assert_eq!(offset, 16);
+        assert_eq!(parsed.original_len, payload.len());
+    }
+
+    #[test]
+    fn test_verify_frame_rejects_garbage() {
+        assert!(!verify_frame(b"no magic here"));
+    }
+}
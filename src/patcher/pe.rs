@@ -7,17 +7,93 @@ pub fn patch_pe(
     data: &[u8],
     string: &str,
     strategy: PatchStrategy,
+    force: bool,
+) -> Result<(Vec<u8>, PatchResult), PatchError> {
+    patch_pe_bytes(data, string.as_bytes(), strategy, force)
+}
+
+/// Same as `patch_pe`, but for an arbitrary byte payload rather than a
+/// UTF-8 string (e.g. a compressed/framed blob from the payload subsystem).
+///
+/// `force` controls what happens when the PE carries an Authenticode
+/// signature: by default patching a signed binary is refused with
+/// `PatchError::SignedBinary`, since any patch invalidates the signature
+/// over the image without fixing it up. With `force`, the certificate table
+/// is stripped (security data directory zeroed, cert bytes truncated from
+/// the overlay) before patching proceeds, so the result is an unsigned but
+/// structurally valid PE rather than one with a signature that no longer
+/// matches its contents.
+pub fn patch_pe_bytes(
+    data: &[u8],
+    bytes: &[u8],
+    strategy: PatchStrategy,
+    force: bool,
 ) -> Result<(Vec<u8>, PatchResult), PatchError> {
     let pe = PE::parse(data)?;
-    let string_bytes = string.as_bytes();
-    let needed_size = string_bytes.len() + 1; // +1 for null terminator
+    let needed_size = bytes.len() + 1; // +1 for null terminator
 
-    match strategy {
-        PatchStrategy::Cave => patch_pe_cave(data, &pe, string_bytes, needed_size),
-        PatchStrategy::Section => patch_pe_section(data, &pe, string_bytes),
-        PatchStrategy::Extend => patch_pe_extend(data, &pe, string_bytes),
+    let mut working = data.to_vec();
+    let mut signature_stripped = false;
+    if let Some((dir_entry_offset, cert_offset, _cert_size)) = find_security_directory(data, &pe) {
+        if !force {
+            return Err(PatchError::SignedBinary);
+        }
+        working.truncate(cert_offset as usize);
+        working[dir_entry_offset..dir_entry_offset + 8].copy_from_slice(&[0u8; 8]);
+        signature_stripped = true;
+    }
+
+    let (mut patched, mut result) = match strategy {
+        PatchStrategy::Cave => patch_pe_cave(&working, &pe, bytes, needed_size)?,
+        PatchStrategy::Section => patch_pe_section(&working, &pe, bytes)?,
+        PatchStrategy::Extend => patch_pe_extend(&working, &pe, bytes)?,
+        PatchStrategy::Note => {
+            return Err(PatchError::PatchFailed(
+                "Note strategy is only supported for ELF binaries".to_string(),
+            ))
+        }
         PatchStrategy::Overlay => unreachable!("Overlay is handled in mod.rs"),
+    };
+
+    // Every strategy here changes file bytes (Section/Extend also rewrite
+    // the section table and grow the file, Cave overwrites cave bytes, and
+    // a forced patch truncates the certificate table), any of which leaves
+    // the optional header's CheckSum stale.
+    fix_pe_checksum(&mut patched, &pe);
+
+    result.signature_stripped = signature_stripped;
+
+    Ok((patched, result))
+}
+
+/// Locate the `IMAGE_DIRECTORY_ENTRY_SECURITY` (index 4) data directory
+/// entry, returning `(entry_file_offset, cert_table_file_offset,
+/// cert_table_size)` when a certificate table is present. Computed by hand
+/// off the optional header's fixed layout rather than through goblin, since
+/// we need the exact on-disk byte offset to zero it out. Unlike every other
+/// data directory, the Security entry's first field is a raw file offset
+/// rather than an RVA - the certificate table lives in the overlay rather
+/// than being mapped into memory.
+pub(crate) fn find_security_directory(data: &[u8], pe: &PE) -> Option<(usize, u32, u32)> {
+    let opt_header_start = pe.header.dos_header.pe_pointer as usize + 4 + 20;
+    // Data directories sit right after `NumberOfRvaAndSizes`, which is the
+    // last fixed field: offset 96 for PE32, 112 for PE32+ (no `BaseOfData`
+    // and wider windows-specific fields).
+    let data_dirs_offset = opt_header_start + if pe.is_64 { 112 } else { 96 };
+    let entry_offset = data_dirs_offset + 4 * 8;
+
+    if entry_offset + 8 > data.len() {
+        return None;
     }
+
+    let cert_offset = u32::from_le_bytes(data[entry_offset..entry_offset + 4].try_into().ok()?);
+    let cert_size = u32::from_le_bytes(data[entry_offset + 4..entry_offset + 8].try_into().ok()?);
+
+    if cert_size == 0 || cert_offset as usize > data.len() {
+        return None;
+    }
+
+    Some((entry_offset, cert_offset, cert_size))
 }
 
 fn patch_pe_cave(
@@ -88,6 +164,10 @@ fn patch_pe_cave(
                     strategy_used: format!("cave ({})", best_section_name),
                     virtual_address: va,
                     file_offset: Some(cave.file_offset as u64),
+                    codec: None,
+                    uncompressed_size: None,
+                    rs_params: None,
+                    signature_stripped: false,
                 },
             ))
         }
@@ -106,9 +186,6 @@ fn patch_pe_section(
     pe: &PE,
     string_bytes: &[u8],
 ) -> Result<(Vec<u8>, PatchResult), PatchError> {
-    // Add a new .rtstr section
-    let mut patched = data.to_vec();
-
     // Calculate alignment
     let file_alignment = pe
         .header
@@ -126,22 +203,6 @@ fn patch_pe_section(
         "No sections found in PE".to_string(),
     ))?;
 
-    let raw_data_end =
-        last_section.pointer_to_raw_data as usize + last_section.size_of_raw_data as usize;
-    let virtual_end = last_section.virtual_address as usize + last_section.virtual_size as usize;
-
-    // Align to file alignment
-    let new_section_offset = align_up(raw_data_end, file_alignment);
-    let new_section_va = align_up(virtual_end, section_alignment) as u32;
-    let new_section_size = align_up(string_bytes.len() + 1, file_alignment);
-
-    // Extend file to accommodate new section data
-    patched.resize(new_section_offset + new_section_size, 0);
-
-    // Write string data
-    patched[new_section_offset..new_section_offset + string_bytes.len()]
-        .copy_from_slice(string_bytes);
-
     // Update PE headers
     // Find section table offset
     let section_table_offset = pe.header.dos_header.pe_pointer as usize
@@ -159,11 +220,38 @@ fn patch_pe_section(
         .map(|oh| oh.windows_fields.size_of_headers)
         .unwrap_or(0x400) as usize;
 
-    if new_section_entry_offset + 40 > headers_size {
-        return Err(PatchError::PatchFailed(
-            "No space for new section header".to_string(),
-        ));
-    }
+    // If the section table is already packed against SizeOfHeaders, grow the
+    // header region by one file-alignment unit instead of giving up: every
+    // section's raw data shifts down by that amount, PointerToRawData and
+    // SizeOfHeaders are rewritten to match, and the freed space at the end
+    // of the (now larger) header region holds the new section header. This
+    // makes the strategy succeed on every PE instead of only loosely-packed
+    // ones.
+    let (mut patched, raw_data_shift) = if new_section_entry_offset + 40 > headers_size {
+        (
+            rebuild_pe_header_room(data, pe, file_alignment),
+            file_alignment as u64,
+        )
+    } else {
+        (data.to_vec(), 0u64)
+    };
+
+    let raw_data_end = last_section.pointer_to_raw_data as u64
+        + raw_data_shift
+        + last_section.size_of_raw_data as u64;
+    let virtual_end = last_section.virtual_address as usize + last_section.virtual_size as usize;
+
+    // Align to file alignment
+    let new_section_offset = align_up(raw_data_end as usize, file_alignment);
+    let new_section_va = align_up(virtual_end, section_alignment) as u32;
+    let new_section_size = align_up(string_bytes.len() + 1, file_alignment);
+
+    // Extend file to accommodate new section data
+    patched.resize(new_section_offset + new_section_size, 0);
+
+    // Write string data
+    patched[new_section_offset..new_section_offset + string_bytes.len()]
+        .copy_from_slice(string_bytes);
 
     // Write new section header
     let section_header = create_section_header(
@@ -211,6 +299,10 @@ fn patch_pe_section(
             strategy_used: "section (.rtstr)".to_string(),
             virtual_address: Some(image_base + new_section_va as u64),
             file_offset: Some(new_section_offset as u64),
+            codec: None,
+            uncompressed_size: None,
+            rs_params: None,
+            signature_stripped: false,
         },
     ))
 }
@@ -285,6 +377,10 @@ fn patch_pe_extend(
             ),
             virtual_address: Some(image_base + va_offset),
             file_offset: Some(write_offset as u64),
+            codec: None,
+            uncompressed_size: None,
+            rs_params: None,
+            signature_stripped: false,
         },
     ))
 }
@@ -308,6 +404,83 @@ fn calculate_va_from_offset(pe: &PE, file_offset: usize) -> Option<u64> {
     None
 }
 
+/// Recompute the PE optional header's `CheckSum` field in place. Windows'
+/// own loader ignores it for most images, but driver loaders and some AV
+/// heuristics validate it, and `patch_pe_section`/`patch_pe_extend` leave it
+/// stale since they only touch the section table and raw section bytes.
+///
+/// This is the standard PE checksum algorithm (the same one `IMAGEHLP`'s
+/// `CheckSumMappedFile` uses): sum the whole file as little-endian 16-bit
+/// words into a 32-bit accumulator with the CheckSum field itself treated
+/// as zero, folding carries back in after every add, then add the file
+/// length.
+fn fix_pe_checksum(data: &mut [u8], pe: &PE) {
+    let checksum_offset = pe.header.dos_header.pe_pointer as usize + 4 + 20 + 64;
+    if checksum_offset + 4 > data.len() {
+        return;
+    }
+
+    data[checksum_offset..checksum_offset + 4].copy_from_slice(&[0, 0, 0, 0]);
+
+    let mut sum: u32 = 0;
+    let mut words = data.chunks_exact(2);
+    for word in &mut words {
+        sum += u16::from_le_bytes([word[0], word[1]]) as u32;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    if let [low_byte] = words.remainder() {
+        sum += *low_byte as u32;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    // Final double fold of the standard algorithm. The running fold above
+    // already keeps `sum` within 16 bits, so these leave it unchanged.
+    sum = (sum & 0xffff) + (sum >> 16);
+    sum = (sum & 0xffff) + (sum >> 16);
+
+    let checksum = sum + data.len() as u32;
+    data[checksum_offset..checksum_offset + 4].copy_from_slice(&checksum.to_le_bytes());
+}
+
+/// Grow the header region by one `file_alignment` unit, shifting every
+/// section's raw data down by that amount, so there's always room for one
+/// more section header regardless of how tightly the existing table was
+/// packed against `SizeOfHeaders`.
+fn rebuild_pe_header_room(data: &[u8], pe: &PE, file_alignment: usize) -> Vec<u8> {
+    let shift = file_alignment;
+    let old_headers_size = pe
+        .header
+        .optional_header
+        .map(|oh| oh.windows_fields.size_of_headers)
+        .unwrap_or(0x400) as usize;
+    let new_headers_size = old_headers_size + shift;
+
+    let mut out = vec![0u8; data.len() + shift];
+    out[..old_headers_size].copy_from_slice(&data[..old_headers_size]);
+    out[new_headers_size..].copy_from_slice(&data[old_headers_size..]);
+
+    let section_table_offset = pe.header.dos_header.pe_pointer as usize
+        + 4
+        + 20
+        + pe.header.coff_header.size_of_optional_header as usize;
+    let num_sections = pe.header.coff_header.number_of_sections as usize;
+    for i in 0..num_sections {
+        let ptr_offset = section_table_offset + i * 40 + 20; // PointerToRawData
+        let old_ptr = u32::from_le_bytes(out[ptr_offset..ptr_offset + 4].try_into().unwrap());
+        if old_ptr != 0 {
+            out[ptr_offset..ptr_offset + 4]
+                .copy_from_slice(&(old_ptr + shift as u32).to_le_bytes());
+        }
+    }
+
+    // SizeOfHeaders sits right before CheckSum, at optional-header offset 60.
+    let size_of_headers_offset = pe.header.dos_header.pe_pointer as usize + 4 + 20 + 60;
+    out[size_of_headers_offset..size_of_headers_offset + 4]
+        .copy_from_slice(&(new_headers_size as u32).to_le_bytes());
+
+    out
+}
+
 fn align_up(value: usize, alignment: usize) -> usize {
     if alignment == 0 {
         return value;
@@ -0,0 +1,429 @@
+//! Corruption-resilient watermark framing via a systematic Reed-Solomon
+//! erasure code over GF(256).
+//!
+//! `patch` embeds the raw tracking string; that's one contiguous run of
+//! bytes, so a single overwritten byte or a strip pass that clips the code
+//! cave destroys it. This module wraps the string in an RS(n, k) codeword
+//! instead: the string is padded to `k` data symbols, `n - k` parity
+//! symbols are derived from a systematic generator matrix, and `recover`
+//! can reconstruct the original bytes from any `k` surviving symbols (pure
+//! erasures) or from all `n` symbols with up to `(n - k) / 2` of them
+//! silently corrupted (unknown-position errors), per the usual RS
+//! singleton-bound tradeoff `2 * errors + erasures <= n - k`.
+//!
+//! Unknown-position error correction is done by minimum-distance decoding
+//! over combinations of surviving symbols rather than syndrome/Berlekamp-
+//! Massey decoding: for the short (n, k) this crate uses, the number of
+//! candidate combinations is small, so trying each one and keeping the
+//! candidate that best agrees with the observed codeword is simpler than a
+//! general decoder and just as correct. The search is capped so a
+//! pathological (n, k) can't make `recover` hang.
+
+use super::PatchError;
+
+/// Marks the start of a resilient frame so `recover` can locate and bound
+/// it inside a scanned buffer, the same way `payload::PAYLOAD_MAGIC` does
+/// for compressed payload frames.
+const RESILIENT_MAGIC: &[u8; 4] = b"BRRS";
+const FRAME_VERSION: u8 = 1;
+const HEADER_LEN: usize = RESILIENT_MAGIC.len() + 1 + 1 + 1 + 1; // magic+version+n+k+data_len
+
+/// Cap on the number of k-of-available-symbols combinations `recover`
+/// tries before giving up, so a large (n, k) can't make decoding hang.
+const MAX_COMBINATIONS: usize = 20_000;
+
+/// A tracking string recovered from a (possibly damaged) RS frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered {
+    pub string: String,
+    pub n: u8,
+    pub k: u8,
+    /// Symbols missing entirely from the scanned buffer (e.g. the frame
+    /// was truncated by a strip pass).
+    pub erasures: usize,
+    /// Symbols present but corrected because their value disagreed with
+    /// every other surviving symbol.
+    pub corrected_errors: usize,
+}
+
+/// GF(2^8) multiplication with the QR-code reduction polynomial
+/// `x^8 + x^4 + x^3 + x^2 + 1` (0x11D).
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut result: u8 = 0;
+    for _ in 0..8 {
+        if b & 1 == 1 {
+            result ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1D;
+        }
+        b >>= 1;
+    }
+    result
+}
+
+/// GF(2^8) exponentiation by repeated squaring. `a` must be nonzero.
+fn gf_pow(a: u8, mut e: u8) -> u8 {
+    let mut result: u8 = 1;
+    let mut base = a;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        e >>= 1;
+    }
+    result
+}
+
+/// GF(2^8) multiplicative inverse via `a^254` (the field's nonzero
+/// elements form a group of order 255, so `a^255 == 1`). `a` must be
+/// nonzero.
+fn gf_inv(a: u8) -> u8 {
+    debug_assert_ne!(a, 0, "GF(256) zero has no multiplicative inverse");
+    gf_pow(a, 254)
+}
+
+/// The `i`-th of `n` distinct nonzero field elements used as Vandermonde
+/// evaluation points. `n` is capped at 255, so `i + 1` never wraps.
+fn alpha(i: usize) -> u8 {
+    (i + 1) as u8
+}
+
+fn mat_vec_mul(m: &[Vec<u8>], v: &[u8]) -> Vec<u8> {
+    m.iter()
+        .map(|row| {
+            row.iter()
+                .zip(v)
+                .fold(0u8, |acc, (&a, &b)| acc ^ gf_mul(a, b))
+        })
+        .collect()
+}
+
+/// Invert a square matrix over GF(256) via Gauss-Jordan elimination
+/// (subtraction is XOR in characteristic 2). Returns `None` if singular.
+fn invert_matrix(m: &[Vec<u8>]) -> Option<Vec<Vec<u8>>> {
+    let size = m.len();
+    let mut a = m.to_vec();
+    let mut inv: Vec<Vec<u8>> = (0..size)
+        .map(|i| (0..size).map(|j| u8::from(i == j)).collect())
+        .collect();
+
+    for col in 0..size {
+        let pivot_row = (col..size).find(|&r| a[r][col] != 0)?;
+        a.swap(col, pivot_row);
+        inv.swap(col, pivot_row);
+
+        let pivot_inv = gf_inv(a[col][col]);
+        for j in 0..size {
+            a[col][j] = gf_mul(a[col][j], pivot_inv);
+            inv[col][j] = gf_mul(inv[col][j], pivot_inv);
+        }
+
+        for row in 0..size {
+            if row == col {
+                continue;
+            }
+            let factor = a[row][col];
+            if factor == 0 {
+                continue;
+            }
+            for j in 0..size {
+                a[row][j] ^= gf_mul(factor, a[col][j]);
+                inv[row][j] ^= gf_mul(factor, inv[col][j]);
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+/// Build the `n x k` systematic generator matrix `G` for RS(n, k): the
+/// `n x k` Vandermonde matrix `V` (rows = evaluation points, columns =
+/// powers `0..k`), made systematic by right-multiplying with the inverse
+/// of its top `k x k` block so `G[0..k]` comes out as the identity matrix.
+fn build_generator_matrix(n: usize, k: usize) -> Result<Vec<Vec<u8>>, PatchError> {
+    let v: Vec<Vec<u8>> = (0..n)
+        .map(|i| {
+            let a = alpha(i);
+            (0..k).map(|j| gf_pow(a, j as u8)).collect()
+        })
+        .collect();
+
+    let top_inv = invert_matrix(&v[..k]).ok_or_else(|| {
+        PatchError::PatchFailed("Reed-Solomon Vandermonde matrix is not invertible".to_string())
+    })?;
+
+    Ok(v.iter()
+        .map(|row| {
+            (0..k)
+                .map(|c| (0..k).fold(0u8, |acc, j| acc ^ gf_mul(row[j], top_inv[j][c])))
+                .collect()
+        })
+        .collect())
+}
+
+/// Systematically encode `data` (at most `k` bytes, zero-padded) into an
+/// `n`-symbol RS codeword whose first `k` symbols are the padded data.
+fn encode(data: &[u8], n: u8, k: u8) -> Result<Vec<u8>, PatchError> {
+    let (n, k) = (n as usize, k as usize);
+    if k == 0 || n <= k || n > 255 {
+        return Err(PatchError::PatchFailed(format!(
+            "invalid Reed-Solomon parameters (n={}, k={})",
+            n, k
+        )));
+    }
+    if data.len() > k {
+        return Err(PatchError::StringTooLong);
+    }
+
+    let mut padded = vec![0u8; k];
+    padded[..data.len()].copy_from_slice(data);
+
+    let g = build_generator_matrix(n, k)?;
+    Ok(mat_vec_mul(&g, &padded))
+}
+
+/// Frame a tracking string as `magic | version(1) | n(1) | k(1) |
+/// data_len(1) | codeword(n)`.
+pub fn frame(string: &str, n: u8, k: u8) -> Result<Vec<u8>, PatchError> {
+    let bytes = string.as_bytes();
+    if bytes.len() > k as usize {
+        return Err(PatchError::StringTooLong);
+    }
+
+    let codeword = encode(bytes, n, k)?;
+    let mut out = Vec::with_capacity(HEADER_LEN + codeword.len());
+    out.extend_from_slice(RESILIENT_MAGIC);
+    out.push(FRAME_VERSION);
+    out.push(n);
+    out.push(k);
+    out.push(bytes.len() as u8);
+    out.extend_from_slice(&codeword);
+    Ok(out)
+}
+
+/// Recursively collect up to `cap` `k`-element combinations of `items`.
+fn combinations(items: &[usize], k: usize, cap: usize) -> Vec<Vec<usize>> {
+    let mut results = Vec::new();
+    let mut current = Vec::with_capacity(k);
+    combinations_from(items, k, 0, &mut current, &mut results, cap);
+    results
+}
+
+fn combinations_from(
+    items: &[usize],
+    k: usize,
+    start: usize,
+    current: &mut Vec<usize>,
+    results: &mut Vec<Vec<usize>>,
+    cap: usize,
+) {
+    if results.len() >= cap {
+        return;
+    }
+    if current.len() == k {
+        results.push(current.clone());
+        return;
+    }
+    for i in start..items.len() {
+        if results.len() >= cap {
+            return;
+        }
+        current.push(items[i]);
+        combinations_from(items, k, i + 1, current, results, cap);
+        current.pop();
+    }
+}
+
+/// Decode `observed` (length `n`, `None` marking an erased symbol) back
+/// into the original `k` data bytes, correcting up to `(n - k) / 2`
+/// unknown-position errors among the surviving symbols on top of any
+/// explicit erasures, per `2 * errors + erasures <= n - k`.
+fn decode_with_erasures(
+    observed: &[Option<u8>],
+    n: usize,
+    k: usize,
+) -> Result<(Vec<u8>, usize), PatchError> {
+    let g = build_generator_matrix(n, k)?;
+    let available: Vec<usize> = (0..n).filter(|&i| observed[i].is_some()).collect();
+    let erasures = n - available.len();
+
+    if available.len() < k {
+        return Err(PatchError::RecoveryFailed(format!(
+            "not enough surviving symbols to recover: need {}, have {}",
+            k,
+            available.len()
+        )));
+    }
+
+    let error_budget = (n - k).saturating_sub(erasures) / 2;
+    let mut best: Option<(Vec<u8>, usize)> = None;
+
+    for combo in combinations(&available, k, MAX_COMBINATIONS) {
+        let sub_g: Vec<Vec<u8>> = combo.iter().map(|&i| g[i].clone()).collect();
+        let Some(sub_g_inv) = invert_matrix(&sub_g) else {
+            continue;
+        };
+        let values: Vec<u8> = combo.iter().map(|&i| observed[i].unwrap()).collect();
+        let data = mat_vec_mul(&sub_g_inv, &values);
+        let codeword = mat_vec_mul(&g, &data);
+
+        let mismatches = available
+            .iter()
+            .filter(|&&i| codeword[i] != observed[i].unwrap())
+            .count();
+
+        let is_better = best.as_ref().map_or(true, |(_, m)| mismatches < *m);
+        if is_better {
+            best = Some((data, mismatches));
+            if mismatches == 0 {
+                break;
+            }
+        }
+    }
+
+    let (data, mismatches) = best.ok_or_else(|| {
+        PatchError::RecoveryFailed("no valid combination of surviving symbols decoded".to_string())
+    })?;
+
+    if mismatches > error_budget {
+        return Err(PatchError::RecoveryFailed(format!(
+            "{} erasure(s) plus at least {} mismatched symbol(s) exceed the (n-k)={} correction budget",
+            erasures,
+            mismatches,
+            n - k
+        )));
+    }
+
+    Ok((data, mismatches))
+}
+
+/// Locate and parse a resilient frame anywhere in `data`, treating any
+/// symbol past the end of the buffer (a truncated frame) as an erasure,
+/// and reconstruct the original tracking string.
+pub fn find_and_recover(data: &[u8]) -> Result<Recovered, PatchError> {
+    let offset = data
+        .windows(RESILIENT_MAGIC.len())
+        .position(|w| w == *RESILIENT_MAGIC)
+        .ok_or_else(|| PatchError::RecoveryFailed("no resilient frame magic found".to_string()))?;
+
+    if offset + HEADER_LEN > data.len() {
+        return Err(PatchError::RecoveryFailed(
+            "truncated resilient frame header".to_string(),
+        ));
+    }
+
+    let version = data[offset + 4];
+    if version != FRAME_VERSION {
+        return Err(PatchError::RecoveryFailed(format!(
+            "unsupported resilient frame version {}",
+            version
+        )));
+    }
+
+    let n = data[offset + 5];
+    let k = data[offset + 6];
+    let data_len = data[offset + 7] as usize;
+    if k == 0 || n <= k || data_len > k as usize {
+        return Err(PatchError::RecoveryFailed(
+            "malformed resilient frame header".to_string(),
+        ));
+    }
+
+    let codeword_start = offset + HEADER_LEN;
+    let available_len = data.len().saturating_sub(codeword_start).min(n as usize);
+    let observed: Vec<Option<u8>> = (0..n as usize)
+        .map(|i| {
+            if i < available_len {
+                Some(data[codeword_start + i])
+            } else {
+                None
+            }
+        })
+        .collect();
+    let erasures = n as usize - available_len;
+
+    let (decoded, corrected_errors) = decode_with_erasures(&observed, n as usize, k as usize)?;
+    let string = String::from_utf8(decoded[..data_len].to_vec()).map_err(|e| {
+        PatchError::RecoveryFailed(format!("recovered bytes are not valid UTF-8: {}", e))
+    })?;
+
+    Ok(Recovered {
+        string,
+        n,
+        k,
+        erasures,
+        corrected_errors,
+    })
+}
+
+/// Check that a resilient frame is present and recoverable, the way
+/// `payload::verify_frame` checks for a compressed payload frame.
+pub fn verify_frame(data: &[u8]) -> bool {
+    find_and_recover(data).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip_no_corruption() {
+        let framed = frame("TRACKER123", 20, 16).unwrap();
+        let recovered = find_and_recover(&framed).unwrap();
+        assert_eq!(recovered.string, "TRACKER123");
+        assert_eq!(recovered.erasures, 0);
+        assert_eq!(recovered.corrected_errors, 0);
+    }
+
+    #[test]
+    fn test_recovers_from_truncated_tail() {
+        let framed = frame("TRACKER123", 20, 16).unwrap();
+        // Drop the last 4 symbols, as if a strip pass clipped the cave.
+        let truncated = &framed[..framed.len() - 4];
+        let recovered = find_and_recover(truncated).unwrap();
+        assert_eq!(recovered.string, "TRACKER123");
+        assert_eq!(recovered.erasures, 4);
+    }
+
+    #[test]
+    fn test_recovers_from_corrupted_byte() {
+        let mut framed = frame("TRACKER123", 20, 16).unwrap();
+        let last = framed.len() - 1;
+        framed[last] ^= 0xFF;
+        let recovered = find_and_recover(&framed).unwrap();
+        assert_eq!(recovered.string, "TRACKER123");
+        assert_eq!(recovered.corrected_errors, 1);
+    }
+
+    #[test]
+    fn test_too_many_erasures_fails() {
+        let framed = frame("TRACKER123", 20, 16).unwrap();
+        let truncated = &framed[..framed.len() - 10];
+        assert!(find_and_recover(truncated).is_err());
+    }
+
+    #[test]
+    fn test_string_too_long_for_k() {
+        assert!(frame("this string is way too long to fit", 20, 8).is_err());
+    }
+
+    #[test]
+    fn test_find_frame_amid_other_bytes() {
+        let framed = frame("TAG", 12, 8).unwrap();
+        let mut buffer = vec![0u8; 16];
+        buffer.extend_from_slice(&framed);
+        buffer.extend_from_slice(&[0u8; 8]);
+
+        let recovered = find_and_recover(&buffer).unwrap();
+        assert_eq!(recovered.string, "TAG");
+    }
+
+    #[test]
+    fn test_verify_frame_rejects_garbage() {
+        assert!(!verify_frame(b"no magic here"));
+    }
+}
@@ -0,0 +1,349 @@
+use crate::storage::TrackedString;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Triage level of a [`ScanHit`]. `High` means the tracked value was found
+/// whole; `Low` means only its shared prefix matched, which usually means
+/// the string was truncated in the artifact (a stripped symbol, a
+/// string-literal section that got merged or cut) rather than planted
+/// intact, so it's a weaker signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    High,
+    Low,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::High => write!(f, "high"),
+            Severity::Low => write!(f, "low"),
+        }
+    }
+}
+
+/// Length of the leading slice of a tracked value that's searched for on
+/// its own as a `Low`-severity pattern: long enough that it won't fire on
+/// short runs of incidental bytes, short enough to still catch a value
+/// truncated well before its end.
+const PREFIX_MATCH_LEN: usize = 6;
+
+/// A single occurrence of a tracked string found while scanning a file,
+/// reported by [`StringScanner::scan`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanHit {
+    pub id: Uuid,
+    pub value: String,
+    pub tags: Vec<String>,
+    pub offset: usize,
+    /// Whether this occurrence was found as plain ASCII or as the UTF-16LE
+    /// encoding inserted when `wide` scanning is enabled.
+    pub wide: bool,
+    pub severity: Severity,
+}
+
+pub struct StringScanner;
+
+/// Which string and encoding a pattern passed to the automaton came from,
+/// and whether it's the full value or just its shared prefix.
+struct PatternSource {
+    string_idx: usize,
+    wide: bool,
+    severity: Severity,
+}
+
+impl StringScanner {
+    /// Build one Aho-Corasick automaton over every string in `tracked`
+    /// (plus, when `wide` is set, the UTF-16LE encoding of each, and each
+    /// value's shared prefix) and stream `data` through it in a single
+    /// pass, reporting every occurrence with a [`Severity`] so analysts can
+    /// triage a full match from a merely-truncated one.
+    pub fn scan(data: &[u8], tracked: &[TrackedString], wide: bool) -> Vec<ScanHit> {
+        let mut patterns = Vec::new();
+        let mut sources = Vec::new();
+
+        for (string_idx, s) in tracked.iter().enumerate() {
+            patterns.push(s.value.as_bytes().to_vec());
+            sources.push(PatternSource { string_idx, wide: false, severity: Severity::High });
+
+            if wide {
+                patterns.push(utf16le_bytes(&s.value));
+                sources.push(PatternSource { string_idx, wide: true, severity: Severity::High });
+            }
+
+            if s.value.len() > PREFIX_MATCH_LEN {
+                let prefix = &s.value[..PREFIX_MATCH_LEN];
+                patterns.push(prefix.as_bytes().to_vec());
+                sources.push(PatternSource { string_idx, wide: false, severity: Severity::Low });
+
+                if wide {
+                    patterns.push(utf16le_bytes(prefix));
+                    sources.push(PatternSource { string_idx, wide: true, severity: Severity::Low });
+                }
+            }
+        }
+
+        let ac = AhoCorasick::new(&patterns);
+
+        let mut hits: Vec<ScanHit> = ac
+            .find_all(data)
+            .into_iter()
+            .map(|m| {
+                let source = &sources[m.pattern_index];
+                let s = &tracked[source.string_idx];
+                ScanHit {
+                    id: s.id,
+                    value: s.value.clone(),
+                    tags: s.tags.clone(),
+                    offset: m.start_offset(),
+                    wide: source.wide,
+                    severity: source.severity,
+                }
+            })
+            .collect();
+
+        // A `Low` hit at the same id/offset/encoding as a `High` hit is just
+        // the shared prefix of that full match, not a separate truncated
+        // occurrence, so drop it.
+        let high_starts: HashSet<(Uuid, usize, bool)> = hits
+            .iter()
+            .filter(|h| h.severity == Severity::High)
+            .map(|h| (h.id, h.offset, h.wide))
+            .collect();
+        hits.retain(|h| h.severity == Severity::High || !high_starts.contains(&(h.id, h.offset, h.wide)));
+
+        hits
+    }
+}
+
+/// UTF-16LE encoding of `s`, mirroring the `wide` modifier already used by
+/// the `Yara` command for catching wide-string embeddings.
+fn utf16le_bytes(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(|u| u.to_le_bytes()).collect()
+}
+
+/// A single occurrence of a tracked pattern found while scanning a buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanMatch {
+    /// Index into the pattern list passed to [`AhoCorasick::new`].
+    pub pattern_index: usize,
+    /// Byte offset in the scanned buffer where the match ends (exclusive).
+    pub end_offset: usize,
+    /// Length in bytes of the matched pattern.
+    pub len: usize,
+}
+
+impl ScanMatch {
+    /// Byte offset where the match starts.
+    pub fn start_offset(&self) -> usize {
+        self.end_offset - self.len
+    }
+}
+
+struct Node {
+    children: [Option<usize>; 256],
+    fail: usize,
+    /// Indices, into the original pattern list, of every pattern that ends
+    /// at this node (itself or via a failure-link chain, flattened in at
+    /// build time so matching doesn't need to walk the chain per byte).
+    outputs: Vec<usize>,
+}
+
+impl Node {
+    fn new() -> Self {
+        Self {
+            children: [None; 256],
+            fail: 0,
+            outputs: Vec::new(),
+        }
+    }
+}
+
+/// An Aho-Corasick automaton for matching many fixed patterns against a
+/// byte stream in a single O(n) pass, used by the `scan` command to check a
+/// binary against every tracking string in the database at once instead of
+/// scanning once per pattern.
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+    pattern_lens: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Build the automaton's trie, then BFS it to compute failure links:
+    /// each node's failure link points to the node for the longest proper
+    /// suffix of its path that is also a prefix of some pattern, and its
+    /// output set unions in whatever the failure target already matches.
+    pub fn new(patterns: &[Vec<u8>]) -> Self {
+        let mut nodes = vec![Node::new()];
+
+        for (i, pattern) in patterns.iter().enumerate() {
+            let mut node = 0;
+            for &byte in pattern {
+                node = *nodes[node].children[byte as usize].get_or_insert_with(|| {
+                    nodes.push(Node::new());
+                    nodes.len() - 1
+                });
+            }
+            nodes[node].outputs.push(i);
+        }
+
+        let mut queue = VecDeque::new();
+        for byte in 0..256 {
+            if let Some(child) = nodes[0].children[byte] {
+                nodes[child].fail = 0;
+                queue.push_back(child);
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            for byte in 0..256 {
+                let Some(child) = nodes[node].children[byte] else {
+                    continue;
+                };
+
+                let mut fail = nodes[node].fail;
+                while fail != 0 && nodes[fail].children[byte].is_none() {
+                    fail = nodes[fail].fail;
+                }
+                nodes[child].fail = nodes[fail].children[byte].unwrap_or(0);
+                if nodes[child].fail == child {
+                    nodes[child].fail = 0;
+                }
+
+                let fail_outputs = nodes[nodes[child].fail].outputs.clone();
+                nodes[child].outputs.extend(fail_outputs);
+
+                queue.push_back(child);
+            }
+        }
+
+        let pattern_lens = patterns.iter().map(|p| p.len()).collect();
+        Self { nodes, pattern_lens }
+    }
+
+    /// Stream `haystack` through the automaton, reporting every match
+    /// including overlapping ones.
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<ScanMatch> {
+        let mut matches = Vec::new();
+        let mut node = 0;
+
+        for (i, &byte) in haystack.iter().enumerate() {
+            while node != 0 && self.nodes[node].children[byte as usize].is_none() {
+                node = self.nodes[node].fail;
+            }
+            node = self.nodes[node].children[byte as usize].unwrap_or(0);
+
+            for &pattern_index in &self.nodes[node].outputs {
+                matches.push(ScanMatch {
+                    pattern_index,
+                    end_offset: i + 1,
+                    len: self.pattern_lens[pattern_index],
+                });
+            }
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pat(s: &str) -> Vec<u8> {
+        s.as_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_finds_single_pattern() {
+        let ac = AhoCorasick::new(&[pat("RT3xK9mPq2Wv")]);
+        let matches = ac.find_all(b"junkjunkRT3xK9mPq2Wvjunk");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pattern_index, 0);
+        assert_eq!(matches[0].end_offset, 20);
+    }
+
+    #[test]
+    fn test_finds_multiple_patterns_in_one_pass() {
+        let ac = AhoCorasick::new(&[pat("alpha"), pat("beta"), pat("gamma")]);
+        let matches = ac.find_all(b"xxbetaxxalphaxxgammaxx");
+        let found: Vec<usize> = matches.iter().map(|m| m.pattern_index).collect();
+        assert_eq!(found, vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_finds_overlapping_patterns() {
+        let ac = AhoCorasick::new(&[pat("he"), pat("she"), pat("hers")]);
+        let matches = ac.find_all(b"ushershe");
+        let found: Vec<usize> = matches.iter().map(|m| m.pattern_index).collect();
+        assert!(found.contains(&0));
+        assert!(found.contains(&1));
+    }
+
+    #[test]
+    fn test_no_match_returns_empty() {
+        let ac = AhoCorasick::new(&[pat("notpresent")]);
+        assert!(ac.find_all(b"nothing to see here").is_empty());
+    }
+
+    fn tracked(value: &str) -> TrackedString {
+        TrackedString::new(value.to_string(), None, vec!["campaign-1".to_string()])
+    }
+
+    #[test]
+    fn test_scanner_finds_ascii_hit() {
+        let strings = vec![tracked("RT3xK9mPq2Wv")];
+        let haystack = b"...junk...RT3xK9mPq2Wv...junk...";
+        let hits = StringScanner::scan(haystack, &strings, false);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].value, "RT3xK9mPq2Wv");
+        assert_eq!(hits[0].offset, 10);
+        assert!(!hits[0].wide);
+        assert_eq!(hits[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn test_scanner_reports_low_severity_for_truncated_prefix() {
+        let strings = vec![tracked("RT3xK9mPq2Wv")];
+        let haystack = b"...junk...RT3xK9...junk...";
+        let hits = StringScanner::scan(haystack, &strings, false);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].severity, Severity::Low);
+    }
+
+    #[test]
+    fn test_scanner_does_not_double_report_prefix_of_a_full_match() {
+        let strings = vec![tracked("RT3xK9mPq2Wv")];
+        let haystack = b"...junk...RT3xK9mPq2Wv...junk...";
+        let hits = StringScanner::scan(haystack, &strings, false);
+        assert_eq!(hits.iter().filter(|h| h.severity == Severity::Low).count(), 0);
+    }
+
+    #[test]
+    fn test_scanner_finds_wide_hit() {
+        let strings = vec![tracked("RT3xK9mPq2Wv")];
+        let mut haystack = b"junk".to_vec();
+        haystack.extend(utf16le_bytes("RT3xK9mPq2Wv"));
+        let hits = StringScanner::scan(&haystack, &strings, true);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].wide);
+    }
+
+    #[test]
+    fn test_scanner_skips_wide_when_disabled() {
+        let strings = vec![tracked("RT3xK9mPq2Wv")];
+        let haystack = utf16le_bytes("RT3xK9mPq2Wv");
+        let hits = StringScanner::scan(&haystack, &strings, false);
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn test_scanner_reports_tags_and_id() {
+        let strings = vec![tracked("RTabc")];
+        let hits = StringScanner::scan(b"RTabc", &strings, false);
+        assert_eq!(hits[0].id, strings[0].id);
+        assert_eq!(hits[0].tags, vec!["campaign-1".to_string()]);
+    }
+}
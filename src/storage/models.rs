@@ -11,6 +11,15 @@ pub struct TrackedString {
     pub created_at: DateTime<Utc>,
     #[serde(default)]
     pub patched_binaries: Vec<PatchedBinary>,
+    /// Recipient label this string was HKDF-derived for, if it came from the
+    /// keyed attribution mode rather than random/seeded generation.
+    #[serde(default)]
+    pub recipient_label: Option<String>,
+    /// Fingerprint of the master key used to derive this string (never the
+    /// key itself), so a leaked fragment can be traced back to a key without
+    /// the plaintext-to-recipient mapping being stored anywhere.
+    #[serde(default)]
+    pub key_fingerprint: Option<String>,
 }
 
 impl TrackedString {
@@ -22,6 +31,8 @@ impl TrackedString {
             tags,
             created_at: Utc::now(),
             patched_binaries: Vec::new(),
+            recipient_label: None,
+            key_fingerprint: None,
         }
     }
 }
@@ -34,6 +45,23 @@ pub struct PatchedBinary {
     pub strategy: String,
     pub virtual_address: Option<u64>,
     pub file_offset: Option<u64>,
+    /// Set when this patch carried a compressed payload frame rather than a
+    /// plain string: the codec used and the original, uncompressed size.
+    #[serde(default)]
+    pub codec: Option<String>,
+    #[serde(default)]
+    pub uncompressed_size: Option<u64>,
+    /// Set when this patch wrapped the string in a Reed-Solomon RS(n, k)
+    /// frame rather than embedding it raw: the total codeword symbols and
+    /// the data symbols it was padded into, so `Recover` knows the layout.
+    #[serde(default)]
+    pub rs_n: Option<u8>,
+    #[serde(default)]
+    pub rs_k: Option<u8>,
+    /// Set when this patch stripped the target's Authenticode certificate
+    /// table (PE only) because it was signed and `--force` opted in.
+    #[serde(default)]
+    pub signature_stripped: bool,
     pub patched_at: DateTime<Utc>,
 }
 
@@ -46,6 +74,7 @@ pub enum BinaryFormat {
     MachO32,
     MachO64,
     MachOFat,
+    Archive,
     Unknown,
 }
 
@@ -59,6 +88,7 @@ impl std::fmt::Display for BinaryFormat {
             BinaryFormat::MachO32 => write!(f, "Mach-O 32-bit"),
             BinaryFormat::MachO64 => write!(f, "Mach-O 64-bit"),
             BinaryFormat::MachOFat => write!(f, "Mach-O Fat/Universal"),
+            BinaryFormat::Archive => write!(f, "Archive (ar/.lib)"),
             BinaryFormat::Unknown => write!(f, "Unknown"),
         }
     }
@@ -78,7 +78,9 @@ pub fn patch_binary(
         _ => return Err(JsValue::from_str(&format!("Unknown strategy: {}", strategy))),
     };
 
-    let (patched_data, _result) = BinaryPatcher::patch_buffer(data, tracking_string, strategy)
+    // `force` (stripping a signed PE's certificate table) isn't exposed to
+    // WASM callers, same as the payload/resilient patch modes.
+    let (patched_data, _result) = BinaryPatcher::patch_buffer(data, tracking_string, strategy, false)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
     Ok(patched_data)
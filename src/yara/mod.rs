@@ -1,3 +1,5 @@
+use crate::storage::TrackedString;
+use std::collections::BTreeMap;
 use std::fmt::Write;
 
 pub struct YaraGenerator;
@@ -80,6 +82,135 @@ impl YaraGenerator {
         rule
     }
 
+    /// Consolidate every tracked string in `strings` into a single rule, one
+    /// `$s<i>` identifier per value, with the source `TrackedString`'s `id`
+    /// and `created_at` emitted as `meta:` entries so a hit can be traced
+    /// back to the exact breadcrumb record. `require_all` selects `all of
+    /// them` over the default `any of them`.
+    pub fn generate_from_tracked(
+        strings: &[TrackedString],
+        rule_name: Option<&str>,
+        options: &YaraOptions,
+        require_all: bool,
+    ) -> String {
+        let sanitized_name = rule_name
+            .map(Self::sanitize_rule_name)
+            .unwrap_or_else(|| "tracked_strings".to_string());
+
+        let mut rule = String::new();
+
+        writeln!(rule, "rule {} {{", sanitized_name).unwrap();
+        writeln!(rule, "    meta:").unwrap();
+        writeln!(
+            rule,
+            "        description = \"Detects {} tracked breadcrumb(s) from the bREDcrumb database\"",
+            strings.len()
+        )
+        .unwrap();
+        writeln!(rule, "        author = \"redteamstrings\"").unwrap();
+        writeln!(
+            rule,
+            "        date = \"{}\"",
+            chrono::Utc::now().format("%Y-%m-%d")
+        )
+        .unwrap();
+        for (i, tracked) in strings.iter().enumerate() {
+            writeln!(rule, "        s{}_id = \"{}\"", i, tracked.id).unwrap();
+            writeln!(
+                rule,
+                "        s{}_created_at = \"{}\"",
+                i,
+                tracked.created_at.format("%Y-%m-%d")
+            )
+            .unwrap();
+        }
+        writeln!(rule).unwrap();
+
+        if strings.is_empty() {
+            // An empty `--from-db`/`--tag` match has no strings to put in a
+            // `strings:` section, and a `them`-based condition with nothing
+            // to reference doesn't compile. Emit a rule that's valid but
+            // never matches, rather than invalid YARA.
+            writeln!(rule, "    condition:").unwrap();
+            writeln!(rule, "        false").unwrap();
+            write!(rule, "}}").unwrap();
+            return rule;
+        }
+
+        writeln!(rule, "    strings:").unwrap();
+
+        let mut modifiers = Vec::new();
+        if options.ascii {
+            modifiers.push("ascii");
+        }
+        if options.wide {
+            modifiers.push("wide");
+        }
+        if options.nocase {
+            modifiers.push("nocase");
+        }
+        if options.fullword {
+            modifiers.push("fullword");
+        }
+
+        let modifier_str = if modifiers.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", modifiers.join(" "))
+        };
+
+        for (i, tracked) in strings.iter().enumerate() {
+            writeln!(
+                rule,
+                "        $s{} = \"{}\"{}",
+                i,
+                Self::escape_string(&tracked.value),
+                modifier_str
+            )
+            .unwrap();
+        }
+
+        writeln!(rule).unwrap();
+        writeln!(rule, "    condition:").unwrap();
+        writeln!(
+            rule,
+            "        {} of them",
+            if require_all { "all" } else { "any" }
+        )
+        .unwrap();
+        write!(rule, "}}").unwrap();
+
+        rule
+    }
+
+    /// Group `strings` by every tag they carry and generate one independent
+    /// `generate_from_tracked` rule per tag, concatenated with a blank line
+    /// between each. A string tagged more than once appears in more than
+    /// one rule (tags aren't a partition here, matching how
+    /// `Storage::list_by_tag` treats them), and untagged strings are
+    /// dropped since there's no tag to name their rule after.
+    pub fn generate_per_tag(
+        strings: &[TrackedString],
+        options: &YaraOptions,
+        require_all: bool,
+    ) -> String {
+        let mut by_tag: BTreeMap<&str, Vec<TrackedString>> = BTreeMap::new();
+        for tracked in strings {
+            for tag in &tracked.tags {
+                by_tag
+                    .entry(tag.as_str())
+                    .or_default()
+                    .push(tracked.clone());
+            }
+        }
+
+        by_tag
+            .into_iter()
+            .map(|(tag, tagged)| Self::generate_from_tracked(&tagged, Some(tag), options, require_all))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
     pub fn generate_hex_only(string: &str, rule_name: Option<&str>) -> String {
         let sanitized_name = rule_name
             .map(Self::sanitize_rule_name)
@@ -183,4 +314,72 @@ mod tests {
         let hex = YaraGenerator::to_hex_pattern("ABC");
         assert_eq!(hex, "41 42 43");
     }
+
+    #[test]
+    fn test_generate_from_tracked_consolidates_strings() {
+        let strings = vec![
+            TrackedString::new("RT_ALICE".to_string(), None, vec!["campaign-1".to_string()]),
+            TrackedString::new("RT_BOB".to_string(), None, vec!["campaign-1".to_string()]),
+        ];
+        let options = YaraOptions {
+            ascii: true,
+            ..Default::default()
+        };
+        let rule = YaraGenerator::generate_from_tracked(&strings, None, &options, false);
+
+        assert!(rule.contains("$s0 = \"RT_ALICE\""));
+        assert!(rule.contains("$s1 = \"RT_BOB\""));
+        assert!(rule.contains(&format!("s0_id = \"{}\"", strings[0].id)));
+        assert!(rule.contains(&format!("s1_id = \"{}\"", strings[1].id)));
+        assert!(rule.contains("any of them"));
+    }
+
+    #[test]
+    fn test_generate_from_tracked_require_all() {
+        let strings = vec![TrackedString::new(
+            "RT_ONLY".to_string(),
+            None,
+            Vec::new(),
+        )];
+        let rule = YaraGenerator::generate_from_tracked(&strings, None, &YaraOptions::default(), true);
+        assert!(rule.contains("all of them"));
+    }
+
+    #[test]
+    fn test_generate_from_tracked_empty_is_valid_stub() {
+        let rule = YaraGenerator::generate_from_tracked(&[], None, &YaraOptions::default(), false);
+        assert!(!rule.contains("strings:"));
+        assert!(rule.contains("condition:"));
+        assert!(rule.contains("false"));
+        assert!(!rule.contains("of them"));
+    }
+
+    #[test]
+    fn test_generate_per_tag_splits_by_tag() {
+        let strings = vec![
+            TrackedString::new(
+                "RT_ALICE".to_string(),
+                None,
+                vec!["campaign-1".to_string()],
+            ),
+            TrackedString::new(
+                "RT_BOB".to_string(),
+                None,
+                vec!["campaign-2".to_string()],
+            ),
+            TrackedString::new(
+                "RT_BOTH".to_string(),
+                None,
+                vec!["campaign-1".to_string(), "campaign-2".to_string()],
+            ),
+            TrackedString::new("RT_UNTAGGED".to_string(), None, Vec::new()),
+        ];
+        let rule = YaraGenerator::generate_per_tag(&strings, &YaraOptions::default(), false);
+
+        assert_eq!(rule.matches("rule campaign_1").count(), 1);
+        assert_eq!(rule.matches("rule campaign_2").count(), 1);
+        assert!(rule.contains("$s0 = \"RT_ALICE\"") || rule.contains("$s1 = \"RT_ALICE\""));
+        assert!(rule.contains("RT_BOTH"));
+        assert!(!rule.contains("RT_UNTAGGED"));
+    }
 }